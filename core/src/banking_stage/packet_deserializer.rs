@@ -69,6 +69,37 @@ impl PacketReceiverStats {
             }
         }
     }
+
+    /// Report the received and per-category drop counts for banking thread `id`
+    pub fn report(&self, id: u32) {
+        datapoint_info!(
+            "banking_stage-packet_receiver_stats",
+            "id" => id.to_string(),
+            ("passed_sigverify_count", self.passed_sigverify_count, i64),
+            ("failed_sigverify_count", self.failed_sigverify_count, i64),
+            (
+                "failed_sanitization_count",
+                self.failed_sanitization_count,
+                i64
+            ),
+            (
+                "failed_prioritization_count",
+                self.failed_prioritization_count,
+                i64
+            ),
+            ("invalid_vote_count", self.invalid_vote_count, i64),
+            (
+                "excessive_precompile_count",
+                self.excessive_precompile_count,
+                i64
+            ),
+            (
+                "insufficient_compute_limit_count",
+                self.insufficient_compute_limit_count,
+                i64
+            ),
+        );
+    }
 }
 
 impl PacketDeserializer {
@@ -130,6 +161,13 @@ impl PacketDeserializer {
             }
         }
 
+        trace!(
+            "deserialized {} of {} packets: {:?}",
+            deserialized_packets.len(),
+            packet_count,
+            packet_stats
+        );
+
         ReceivePacketResults {
             deserialized_packets,
             packet_stats,
@@ -270,4 +308,22 @@ mod tests {
         assert_eq!(results.packet_stats.passed_sigverify_count, 1);
         assert_eq!(results.packet_stats.failed_sigverify_count, 1);
     }
+
+    #[test]
+    fn test_packet_receiver_stats_report() {
+        let transactions = vec![random_transfer(), random_transfer()];
+        let mut packet_batches = to_packet_batches(&transactions, 1);
+        packet_batches[0][0].meta_mut().set_discard(true);
+        let results = PacketDeserializer::deserialize_and_collect_packets(
+            2,
+            &[BankingPacketBatch::new(packet_batches)],
+            |_| Err(PacketFilterFailure::ExcessivePrecompiles),
+        );
+        let stats = results.packet_stats;
+        assert_eq!(stats.failed_sigverify_count, 1);
+        assert_eq!(stats.excessive_precompile_count, 1);
+
+        stats.report(0);
+        stats.report(u32::MAX);
+    }
 }
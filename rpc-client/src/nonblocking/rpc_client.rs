@@ -4245,6 +4245,33 @@ impl RpcClient {
 
         Ok(result?.value)
     }
+
+    /// Like [`distribute_reward_to_account`](Self::distribute_reward_to_account), but the
+    /// node applies a reward at most once per `dedup_key`, so the request can be retried.
+    ///
+    /// Errors keep their original kind, so transport failures can be told apart from
+    /// errors returned by the node.
+    pub async fn distribute_reward_to_account_with_dedup_key(
+        &self,
+        pubkey: &Pubkey,
+        amount: u64,
+        dedup_key: &str,
+        auth_token: String,
+    ) -> ClientResult<Option<AccountSharedData>> {
+        let response: Option<Response<Option<AccountSharedData>>> = self
+            .send_with_auth_token(
+                RpcRequest::DistributeRewardToAccount,
+                auth_token,
+                json!([pubkey.to_string(), amount, dedup_key]),
+            )
+            .await?;
+        let Response { value, .. } = response.ok_or_else(|| {
+            ClientError::from(RpcError::ForUser(format!(
+                "Distribute reward to pubkey {pubkey} error"
+            )))
+        })?;
+        Ok(value)
+    }
     pub async fn get_token_account_with_commitment(
         &self,
         pubkey: &Pubkey,
@@ -3421,6 +3421,19 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).distribute_reward_to_account(pubkey, amount, auth_token))
     }
 
+    pub fn distribute_reward_to_account_with_dedup_key(
+        &self,
+        pubkey: &Pubkey,
+        amount: u64,
+        dedup_key: &str,
+        auth_token: String,
+    ) -> ClientResult<Option<AccountSharedData>> {
+        self.invoke(
+            (self.rpc_client.as_ref())
+                .distribute_reward_to_account_with_dedup_key(pubkey, amount, dedup_key, auth_token),
+        )
+    }
+
     pub fn get_health(&self) -> ClientResult<()> {
         self.invoke((self.rpc_client.as_ref()).get_health())
     }
@@ -109,13 +109,13 @@ use {
     std::{
         any::type_name,
         cmp::{max, min, Reverse},
-        collections::{BinaryHeap, HashMap, HashSet},
+        collections::{BinaryHeap, HashMap, HashSet, VecDeque},
         convert::TryFrom,
         net::SocketAddr,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
-            Arc, RwLock,
+            Arc, Mutex, RwLock,
         },
         time::Duration,
     },
@@ -141,6 +141,8 @@ type RpcCustomResult<T> = std::result::Result<T, RpcCustomError>;
 
 pub const MAX_REQUEST_BODY_SIZE: usize = 50 * (1 << 10); // 50kB
 pub const PERFORMANCE_SAMPLES_LIMIT: usize = 720;
+/// Dedup keys of applied rewards remembered by `distributeRewardToAccount`
+pub const MAX_APPLIED_REWARD_KEYS: usize = 100_000;
 
 fn new_response<T>(bank: &Bank, value: T) -> RpcResponse<T> {
     RpcResponse {
@@ -256,9 +258,30 @@ pub struct JsonRpcRequestProcessor {
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
     runtime: Arc<Runtime>,
+    applied_rewards: Arc<Mutex<AppliedRewards>>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
+/// Rewards applied with a dedup key, so a retried request is applied once
+#[derive(Debug, Default)]
+struct AppliedRewards {
+    rewards: HashMap<String, (Pubkey, u64)>,
+    // Oldest first, for evicting past `MAX_APPLIED_REWARD_KEYS`
+    order: VecDeque<String>,
+}
+
+impl AppliedRewards {
+    fn insert(&mut self, dedup_key: String, recipient: Pubkey, amount: u64) {
+        if self.order.len() == MAX_APPLIED_REWARD_KEYS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.rewards.remove(&oldest);
+            }
+        }
+        self.rewards.insert(dedup_key.clone(), (recipient, amount));
+        self.order.push_back(dedup_key);
+    }
+}
+
 impl JsonRpcRequestProcessor {
     pub fn clone_without_bigtable(&self) -> JsonRpcRequestProcessor {
         Self {
@@ -431,6 +454,7 @@ impl JsonRpcRequestProcessor {
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
                 runtime,
+                applied_rewards: Arc::default(),
             },
             transaction_receiver,
         )
@@ -525,6 +549,7 @@ impl JsonRpcRequestProcessor {
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
             runtime: service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
+            applied_rewards: Arc::default(),
         }
     }
 
@@ -556,10 +581,12 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, response))
     }
     // add by zhmye todo 这里先按照上面写成返回一个account，不然我也不知道返回啥
+    // dedup_key 相同的请求只生效一次，客户端可以安全地重试
     pub async fn distribute_reward_to_account(
         &self,
         recipient: Pubkey,
         amount: u64,
+        dedup_key: Option<String>,
     ) -> Result<RpcResponse<Option<AccountSharedData>>> {
         let RpcAccountInfoConfig {
             encoding,
@@ -568,6 +595,27 @@ impl JsonRpcRequestProcessor {
             min_context_slot,
         } = RpcAccountInfoConfig::default();
         let bank = self.bank_forks.read().unwrap().working_bank();
+        // 带dedup_key时整个过程持有锁，同一dedup_key的并发重试会等待并看到本次已生效；
+        // 不带dedup_key的请求不加锁
+        let applied_rewards = match &dedup_key {
+            Some(dedup_key) => {
+                let applied_rewards = self.applied_rewards.lock().unwrap();
+                if let Some(&(applied_recipient, applied_amount)) =
+                    applied_rewards.rewards.get(dedup_key)
+                {
+                    if (applied_recipient, applied_amount) != (recipient, amount) {
+                        return Err(Error::invalid_params(format!(
+                            "dedup key {dedup_key} was already used for {applied_amount} \
+                             lamports to {applied_recipient}"
+                        )));
+                    }
+                    info!("Reward with dedup key {dedup_key} already applied, not applying again");
+                    return Ok(new_response(&bank, bank.get_account(&recipient)));
+                }
+                Some(applied_rewards)
+            }
+            None => None,
+        };
         let mut account_data = bank
             .get_account(&recipient)
             .unwrap_or_else(|| {
@@ -584,6 +632,9 @@ impl JsonRpcRequestProcessor {
 
         // 使用Bank的store_account函数
         bank.store_account(&recipient, &account_data);
+        if let (Some(mut applied_rewards), Some(dedup_key)) = (applied_rewards, dedup_key) {
+            applied_rewards.insert(dedup_key, recipient, amount);
+        }
         // 然后我们再读一次，用于返回？
         let response = bank.get_account(&recipient);
         // let response = self
@@ -3225,6 +3276,7 @@ pub mod rpc_accounts {
             meta: Self::Metadata,
             pubkey_str: String,
             amount: u64,
+            dedup_key: Option<String>,
         ) -> BoxFuture<Result<RpcResponse<Option<AccountSharedData>>>>;
 
 
@@ -3287,11 +3339,13 @@ pub mod rpc_accounts {
             meta: Self::Metadata,
             pubkey_str: String,
             amount: u64,
+            dedup_key: Option<String>,
         ) -> BoxFuture<Result<RpcResponse<Option<AccountSharedData>>>> {
             debug!("distribute_reward_to_account rpc request received: {:?}", pubkey_str);
             async move {
                 let pubkey = verify_pubkey(&pubkey_str)?;
-                meta.distribute_reward_to_account(pubkey, amount).await
+                meta.distribute_reward_to_account(pubkey, amount, dedup_key)
+                    .await
             }.boxed()
         }
         fn get_multiple_accounts(
@@ -5026,6 +5080,74 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_distribute_reward_to_account_dedup_key() {
+        let recipient = solana_pubkey::new_rand();
+        let genesis = create_genesis_config(100);
+        let bank = Bank::new_for_tests(&genesis.genesis_config);
+        let connection_cache = Arc::new(ConnectionCache::new("connection_cache_test"));
+        let meta = JsonRpcRequestProcessor::new_from_bank(
+            bank,
+            SocketAddrSpace::Unspecified,
+            connection_cache,
+        );
+        let distribute = |recipient, amount, dedup_key: Option<&str>| {
+            futures::executor::block_on(meta.distribute_reward_to_account(
+                recipient,
+                amount,
+                dedup_key.map(str::to_string),
+            ))
+        };
+        let balance =
+            |response: RpcResponse<Option<AccountSharedData>>| response.value.unwrap().lamports();
+
+        assert_eq!(
+            balance(distribute(recipient, 1_000, Some("reward-1")).unwrap()),
+            1_000
+        );
+        // A replayed key returns the account without crediting it again
+        assert_eq!(
+            balance(distribute(recipient, 1_000, Some("reward-1")).unwrap()),
+            1_000
+        );
+        // Reusing the key for another amount or recipient is rejected
+        let err = distribute(recipient, 2_000, Some("reward-1")).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+        assert!(err.message.contains("reward-1"), "{}", err.message);
+        let err = distribute(solana_pubkey::new_rand(), 1_000, Some("reward-1")).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+        assert_eq!(
+            meta.bank_forks
+                .read()
+                .unwrap()
+                .working_bank()
+                .get_balance(&recipient),
+            1_000
+        );
+
+        // Without a key every call is applied, and nothing is remembered
+        assert_eq!(balance(distribute(recipient, 500, None).unwrap()), 1_500);
+        assert_eq!(balance(distribute(recipient, 500, None).unwrap()), 2_000);
+        assert_eq!(meta.applied_rewards.lock().unwrap().order.len(), 1);
+    }
+
+    #[test]
+    fn test_applied_rewards_evicts_oldest_key() {
+        let recipient = solana_pubkey::new_rand();
+        let mut applied_rewards = AppliedRewards::default();
+        for i in 0..=MAX_APPLIED_REWARD_KEYS {
+            applied_rewards.insert(format!("reward-{i}"), recipient, 1);
+        }
+
+        assert_eq!(applied_rewards.order.len(), MAX_APPLIED_REWARD_KEYS);
+        assert_eq!(applied_rewards.rewards.len(), MAX_APPLIED_REWARD_KEYS);
+        assert!(!applied_rewards.rewards.contains_key("reward-0"));
+        assert!(applied_rewards.rewards.contains_key("reward-1"));
+        assert!(applied_rewards
+            .rewards
+            .contains_key(&format!("reward-{MAX_APPLIED_REWARD_KEYS}")));
+    }
+
     #[test]
     fn test_rpc_get_balance() {
         let genesis = create_genesis_config(20);
@@ -28,11 +28,12 @@ use {
         collections::{HashMap, HashSet},
         env, error,
         fmt::{self, Display},
+        future::Future,
         net::SocketAddr,
         path::{Path, PathBuf},
         sync::{Arc, RwLock},
         thread::{self, Builder},
-        time::{Duration, SystemTime},
+        time::{Duration, Instant, SystemTime},
     },
     tokio::runtime::Runtime,
 };
@@ -820,6 +821,43 @@ pub async fn connect(ledger_path: &Path) -> std::result::Result<gen_client::Clie
     }
 }
 
+// How often `wait_ready` retries while the admin RPC socket is not up yet
+const WAIT_READY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Connect to the Admin RPC interface, retrying while the validator starts up.
+// Returns `RpcError::Timeout` if it has not become reachable within `timeout`.
+pub async fn wait_ready(
+    ledger_path: &Path,
+    timeout: Duration,
+) -> std::result::Result<gen_client::Client, RpcError> {
+    retry_until_connected(|| connect(ledger_path), timeout, WAIT_READY_POLL_INTERVAL).await
+}
+
+async fn retry_until_connected<T, F, Fut>(
+    mut connect: F,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> std::result::Result<T, RpcError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, RpcError>>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        match connect().await {
+            Ok(client) => return Ok(client),
+            Err(err) if Instant::now() >= deadline => {
+                warn!("Admin RPC not ready after {timeout:?}: {err}");
+                return Err(RpcError::Timeout);
+            }
+            Err(err) => {
+                debug!("Admin RPC not ready yet: {err}");
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
 pub fn runtime() -> Runtime {
     tokio::runtime::Builder::new_multi_thread()
         .thread_name("solAdminRpcRt")
@@ -1469,4 +1507,32 @@ mod tests {
                 .expect("actual response deserialization");
         assert_eq!(actual_parsed_response, expected_parsed_response);
     }
+
+    #[test]
+    fn test_retry_until_connected() {
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let client = runtime().block_on(retry_until_connected(
+            || {
+                let attempt = attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async move {
+                    if attempt < 2 {
+                        Err(RpcError::Client("admin.rpc does not exist".to_string()))
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            },
+            Duration::from_secs(10),
+            Duration::from_millis(1),
+        ));
+        assert_eq!(client.unwrap(), 2);
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        let never = runtime().block_on(retry_until_connected(
+            || async { Err::<(), _>(RpcError::Client("admin.rpc does not exist".to_string())) },
+            Duration::from_millis(20),
+            Duration::from_millis(1),
+        ));
+        assert!(matches!(never, Err(RpcError::Timeout)));
+    }
 }
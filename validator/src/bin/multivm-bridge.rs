@@ -0,0 +1,379 @@
+use {
+    agave_validator::bridge::{
+        config::MultivmConfig,
+        export::{export_transfers, ExportFormat, ExportOptions},
+        finality::ChainContinuityChecker,
+        ipc::IpcClient,
+        outbox::{Outbox, OutboxConfig, OutboxStatus},
+        selftest::{run_selftest, SelftestOptions},
+        util::validate_jwt_secret,
+    },
+    clap::{value_t_or_exit, App, AppSettings, Arg, ArgMatches, SubCommand},
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::commitment_config::CommitmentConfig,
+    std::{path::Path, process::exit},
+};
+
+const DEFAULT_IPC_SOCKET: &str = "/tmp/solana-private-validator";
+
+fn selftest(matches: &ArgMatches) -> bool {
+    let rpc_url = matches
+        .value_of("rpc_url")
+        .map(str::to_string)
+        .unwrap_or_else(MultivmConfig::rpc_url);
+    let mut rpc_client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::processed());
+    if let Some(jwt_secret) = matches.value_of("jwt_secret") {
+        rpc_client.set_auth_token_secret(jwt_secret.to_string());
+    }
+    let ipc_client = IpcClient::new(matches.value_of("ipc_socket").unwrap().to_string());
+
+    let options = SelftestOptions {
+        skip_readiness: matches.is_present("skip_readiness"),
+        skip_airdrop: matches.is_present("skip_airdrop"),
+        skip_deposit: matches.is_present("skip_deposit"),
+        skip_scan: matches.is_present("skip_scan"),
+        skip_reward: matches.is_present("skip_reward"),
+        ..SelftestOptions::default()
+    };
+    let report = run_selftest(&rpc_client, &ipc_client, &options);
+    print!("{report}");
+    report.passed()
+}
+
+fn export(matches: &ArgMatches) -> bool {
+    let rpc_url = matches
+        .value_of("rpc_url")
+        .map(str::to_string)
+        .unwrap_or_else(MultivmConfig::rpc_url);
+    let rpc_client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    let from_slot = value_t_or_exit!(matches, "from_slot", u64);
+    let to_slot = value_t_or_exit!(matches, "to_slot", u64);
+    let format = match matches.value_of("format").unwrap() {
+        "jsonl" => ExportFormat::JsonLines,
+        _ => ExportFormat::Csv,
+    };
+    let options = ExportOptions {
+        chunk_slots: value_t_or_exit!(matches, "chunk_slots", u64),
+        resume: matches.is_present("resume"),
+    };
+    let out_path = Path::new(matches.value_of("output").unwrap());
+
+    match export_transfers(&rpc_client, from_slot, to_slot, format, out_path, &options) {
+        Ok(summary) => {
+            match summary.resumed_from {
+                Some(slot) => println!(
+                    "Exported {} transfers to {} (resumed at slot {slot})",
+                    summary.rows,
+                    out_path.display()
+                ),
+                None => println!(
+                    "Exported {} transfers to {}",
+                    summary.rows,
+                    out_path.display()
+                ),
+            }
+            true
+        }
+        Err(e) => {
+            eprintln!("Export failed: {e}");
+            false
+        }
+    }
+}
+
+fn finality_acknowledge(matches: &ArgMatches) -> bool {
+    let state_path = Path::new(matches.value_of("state").unwrap());
+    if !state_path.exists() {
+        eprintln!("No continuity state at {}", state_path.display());
+        return false;
+    }
+    let mut checker = match ChainContinuityChecker::open(state_path) {
+        Ok(checker) => checker,
+        Err(e) => {
+            eprintln!("Failed to open {}: {e}", state_path.display());
+            return false;
+        }
+    };
+    let Some(anomaly) = checker.unacknowledged().cloned() else {
+        println!("No unacknowledged chain anomaly");
+        return true;
+    };
+    match checker.acknowledge() {
+        Ok(()) => {
+            println!("Acknowledged {anomaly:?}; deposit emission resumes");
+            true
+        }
+        Err(e) => {
+            eprintln!("Failed to acknowledge {anomaly:?}: {e}");
+            false
+        }
+    }
+}
+
+fn open_outbox(matches: &ArgMatches) -> Option<Outbox> {
+    let dir = Path::new(matches.value_of("dir").unwrap());
+    match Outbox::open(dir, OutboxConfig::default()) {
+        Ok(outbox) => Some(outbox),
+        Err(e) => {
+            eprintln!("Failed to open the outbox in {}: {e}", dir.display());
+            None
+        }
+    }
+}
+
+fn outbox_list(matches: &ArgMatches) -> bool {
+    let Some(outbox) = open_outbox(matches) else {
+        return false;
+    };
+    for entry in outbox.list() {
+        let status = match &entry.status {
+            OutboxStatus::Pending => "pending".to_string(),
+            OutboxStatus::Delivered { receipt_id } => format!("delivered ({receipt_id})"),
+            OutboxStatus::DeadLetter => "dead-letter".to_string(),
+        };
+        println!(
+            "{} {} attempts={} {}",
+            entry.message.source_signature,
+            status,
+            entry.attempts,
+            entry.last_error.as_deref().unwrap_or_default()
+        );
+    }
+    let stats = outbox.stats();
+    println!(
+        "{} pending, {} delivered, {} dead-lettered",
+        stats.pending, stats.delivered, stats.dead_letter
+    );
+    true
+}
+
+fn outbox_update(matches: &ArgMatches, dead_letter: bool) -> bool {
+    let Some(outbox) = open_outbox(matches) else {
+        return false;
+    };
+    let signature = matches.value_of("signature").unwrap();
+    let result = if dead_letter {
+        outbox.dead_letter(signature)
+    } else {
+        outbox.retry(signature)
+    };
+    match result {
+        Ok(true) if dead_letter => {
+            println!("Dead-lettered {signature}");
+            true
+        }
+        Ok(true) => {
+            println!("Requeued {signature}");
+            true
+        }
+        Ok(false) => {
+            eprintln!("No undelivered outbox entry for {signature}");
+            false
+        }
+        Err(e) => {
+            eprintln!("Failed to update {signature}: {e}");
+            false
+        }
+    }
+}
+
+fn main() {
+    solana_logger::setup_with_default("solana=info");
+    let skip = |name: &'static str, long: &'static str, help: &'static str| {
+        Arg::with_name(name)
+            .long(long)
+            .takes_value(false)
+            .help(help)
+    };
+    let outbox_dir = || {
+        Arg::with_name("dir")
+            .long("dir")
+            .value_name("PATH")
+            .takes_value(true)
+            .required(true)
+            .help("Outbox directory")
+    };
+    let outbox_signature = || {
+        Arg::with_name("signature")
+            .value_name("SIGNATURE")
+            .takes_value(true)
+            .required(true)
+            .help("Source signature of the outbox entry")
+    };
+    let matches = App::new("multivm-bridge")
+        .about("Bridge tooling for the private multivm validator")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("selftest")
+                .about("Check the full bridge loop against a freshly started validator")
+                .arg(
+                    Arg::with_name("rpc_url")
+                        .long("rpc-url")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .help("Validator RPC URL [default: the multivm config RPC URL]"),
+                )
+                .arg(
+                    Arg::with_name("ipc_socket")
+                        .long("ipc-socket")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .default_value(DEFAULT_IPC_SOCKET)
+                        .help("Manual tick IPC socket"),
+                )
+                .arg(
+                    Arg::with_name("jwt_secret")
+                        .long("jwt-secret")
+                        .value_name("HEX")
+                        .takes_value(true)
+                        .validator(|secret| validate_jwt_secret(&secret).map_err(|e| e.to_string()))
+                        .help("Hex JWT secret for the privileged RPC methods"),
+                )
+                .arg(skip(
+                    "skip_readiness",
+                    "skip-readiness",
+                    "Skip the readiness check",
+                ))
+                .arg(skip(
+                    "skip_airdrop",
+                    "skip-airdrop",
+                    "Skip funding the throwaway account",
+                ))
+                .arg(skip(
+                    "skip_deposit",
+                    "skip-deposit",
+                    "Skip the transfer with EVM memo",
+                ))
+                .arg(skip(
+                    "skip_scan",
+                    "skip-scan",
+                    "Skip scanning the deposit back",
+                ))
+                .arg(skip(
+                    "skip_reward",
+                    "skip-reward",
+                    "Skip the reward check on chains without the privileged RPC",
+                )),
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Export bridge transfers in a slot range to a CSV or JSON lines file")
+                .arg(
+                    Arg::with_name("rpc_url")
+                        .long("rpc-url")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .help("Validator RPC URL [default: the multivm config RPC URL]"),
+                )
+                .arg(
+                    Arg::with_name("from_slot")
+                        .long("from-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .required(true)
+                        .help("First slot to export"),
+                )
+                .arg(
+                    Arg::with_name("to_slot")
+                        .long("to-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Last slot to export, inclusive"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .short("o")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Output file; the checkpoint is written next to it"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .possible_values(&["csv", "jsonl"])
+                        .default_value("csv")
+                        .help("Output format"),
+                )
+                .arg(
+                    Arg::with_name("chunk_slots")
+                        .long("chunk-slots")
+                        .value_name("SLOTS")
+                        .takes_value(true)
+                        .default_value("1000")
+                        .help("Slots exported between flushes and checkpoints"),
+                )
+                .arg(
+                    Arg::with_name("resume")
+                        .long("resume")
+                        .takes_value(false)
+                        .help("Continue an interrupted export of the same range"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("outbox")
+                .about(
+                    "Inspect and manage EVM-bound outbox messages; stop the delivery worker \
+                     before changing entries",
+                )
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List every outbox entry with its delivery state")
+                        .arg(outbox_dir()),
+                )
+                .subcommand(
+                    SubCommand::with_name("retry")
+                        .about("Move an undelivered entry back to pending with fresh attempts")
+                        .arg(outbox_dir())
+                        .arg(outbox_signature()),
+                )
+                .subcommand(
+                    SubCommand::with_name("dead-letter")
+                        .about("Stop retrying an undelivered entry")
+                        .arg(outbox_dir())
+                        .arg(outbox_signature()),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("finality")
+                .about("Inspect and manage the chain continuity checker")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("acknowledge")
+                        .about("Resume deposit emission paused by a chain anomaly")
+                        .arg(
+                            Arg::with_name("state")
+                                .long("state")
+                                .value_name("PATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Continuity checker state file"),
+                        ),
+                ),
+        )
+        .get_matches();
+
+    let passed = match matches.subcommand() {
+        ("selftest", Some(matches)) => selftest(matches),
+        ("export", Some(matches)) => export(matches),
+        ("outbox", Some(matches)) => match matches.subcommand() {
+            ("list", Some(matches)) => outbox_list(matches),
+            ("retry", Some(matches)) => outbox_update(matches, false),
+            ("dead-letter", Some(matches)) => outbox_update(matches, true),
+            _ => unreachable!(),
+        },
+        ("finality", Some(matches)) => match matches.subcommand() {
+            ("acknowledge", Some(matches)) => finality_acknowledge(matches),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    if !passed {
+        exit(1);
+    }
+}
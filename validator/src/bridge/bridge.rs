@@ -5,7 +5,7 @@ use solana_client::connection_cache::ConnectionCache;
 use solana_connection_cache::connection_cache::NewConnectionConfig;
 use solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool};
 use solana_rpc_client::rpc_client::RpcClient;
-use solana_rpc_client_api::client_error::Result as ClientResult;
+use solana_rpc_client_api::client_error::{ErrorKind as ClientErrorKind, Result as ClientResult};
 use solana_sdk::hash::Hash;
 use solana_sdk::system_transaction;
 use solana_sdk::{
@@ -20,9 +20,15 @@ use solana_tpu_client::tpu_client::{TpuClient, TpuClientConfig};
 use solana_transaction_error::TransactionResult;
 use tokio::time::timeout;
 
+use crate::bridge::tick::TickDriver;
+use crate::bridge::util::{
+    ensure_balance, request_airdrop_and_confirm, SendConfig, TransferOptions,
+};
+
 pub struct Bridge {
     pub tpu_client: Arc<TpuClient<QuicPool, QuicConnectionManager, QuicConfig>>,
     pub rpc_client: Arc<RpcClient>,
+    pub transfer_options: TransferOptions,
 }
 
 impl Bridge {
@@ -45,18 +51,35 @@ impl Bridge {
             cache,
         )
         .map_err(|e| format!("Failed to build TpuClient: {}", e))?;
-        Ok(Self {
-            tpu_client: Arc::new(tpu_client),
+        Ok(Self::with_clients(rpc_client, Arc::new(tpu_client)))
+    }
+
+    /// Build a bridge over existing clients, with default transfer options
+    pub fn with_clients(
+        rpc_client: Arc<RpcClient>,
+        tpu_client: Arc<TpuClient<QuicPool, QuicConnectionManager, QuicConfig>>,
+    ) -> Self {
+        Self {
+            tpu_client,
             rpc_client,
-        })
+            transfer_options: TransferOptions::default(),
+        }
     }
 
+    /// Send a transfer through the TPU
+    ///
+    /// Zero-lamport transfers and burn-address recipients are rejected as a
+    /// `ClientErrorKind::Custom` unless `transfer_options` allows them.
     pub fn transfer(
         &self,
         from_keypair: &Keypair,
         to_pubkey: &Pubkey,
         lamports: u64,
     ) -> ClientResult<Signature> {
+        self.transfer_options
+            .check_amount(lamports)
+            .and_then(|()| self.transfer_options.check_recipient(to_pubkey))
+            .map_err(|e| ClientErrorKind::Custom(e.to_string()))?;
         let recent_blockhash = self.rpc_client.get_latest_blockhash()?;
         let transaction =
             system_transaction::transfer(from_keypair, to_pubkey, lamports, recent_blockhash);
@@ -74,6 +97,42 @@ impl Bridge {
         Ok(signature)
     }
 
+    /// Request an airdrop and drive ticks until it is processed
+    ///
+    /// On a manual-tick validator `airdrop` alone never confirms.
+    pub fn airdrop_and_confirm(
+        &self,
+        to_pubkey: &Pubkey,
+        lamports: u64,
+        tick_driver: &dyn TickDriver,
+    ) -> Result<Signature, Box<dyn std::error::Error + Send + Sync>> {
+        request_airdrop_and_confirm(
+            tick_driver,
+            &self.rpc_client,
+            to_pubkey,
+            lamports,
+            &SendConfig::default(),
+        )
+    }
+
+    /// Airdrop only what `pubkey` lacks to reach `target`; a no-op if already funded
+    ///
+    /// Returns the airdrop signature, or `None` when no airdrop was needed.
+    pub fn ensure_balance(
+        &self,
+        pubkey: &Pubkey,
+        target: u64,
+        tick_driver: &dyn TickDriver,
+    ) -> Result<Option<Signature>, Box<dyn std::error::Error + Send + Sync>> {
+        ensure_balance(
+            tick_driver,
+            &self.rpc_client,
+            pubkey,
+            target,
+            &SendConfig::default(),
+        )
+    }
+
     pub fn confirm_transaction(&self, signature: &Signature) -> Option<TransactionResult<()>> {
         let now = Instant::now();
         // Wait up to 10 seconds for confirmation.
@@ -134,8 +193,90 @@ impl Bridge {
 mod tests {
     use rand::Rng;
 
+    use serde_json::json;
+    use solana_rpc_client_api::request::RpcRequest;
+    use std::sync::atomic::Ordering;
+
     use super::*;
     use crate::bridge::config::MultivmConfig;
+    use crate::bridge::util::tests::{scripted_rpc_client, CountingTickDriver};
+
+    /// A TPU client over the mock RPC sender, which never reaches a network
+    fn mock_tpu_client() -> Arc<TpuClient<QuicPool, QuicConnectionManager, QuicConfig>> {
+        let ConnectionCache::Quic(cache) = ConnectionCache::new_quic("bridge_test_cache", 1) else {
+            unreachable!("new_quic builds a Quic connection cache");
+        };
+        let rpc_client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+        // An empty websocket url skips the slot subscription
+        Arc::new(
+            TpuClient::new_with_connection_cache(rpc_client, "", TpuClientConfig::default(), cache)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_airdrop_and_confirm_drives_ticks() {
+        let to = Pubkey::new_unique();
+        let airdrop_signature = Signature::from([7u8; 64]);
+        let blockhash = Hash::new_unique();
+        let no_status = || Ok(json!({"context": {"slot": 1}, "value": [null]}));
+        let (rpc_client, rpc_script) = scripted_rpc_client(vec![
+            (
+                RpcRequest::GetLatestBlockhash,
+                Ok(json!({
+                    "context": {"slot": 1},
+                    "value": {
+                        "blockhash": blockhash.to_string(),
+                        "lastValidBlockHeight": 150,
+                    },
+                })),
+            ),
+            (
+                RpcRequest::RequestAirdrop,
+                Ok(json!(airdrop_signature.to_string())),
+            ),
+            (RpcRequest::GetSignatureStatuses, no_status()),
+            (RpcRequest::GetSignatureStatuses, no_status()),
+            (
+                RpcRequest::GetSignatureStatuses,
+                Ok(json!({
+                    "context": {"slot": 3},
+                    "value": [{
+                        "slot": 3,
+                        "confirmations": 0,
+                        "status": {"Ok": null},
+                        "err": null,
+                        "confirmationStatus": "processed",
+                    }],
+                })),
+            ),
+        ]);
+        let bridge = Bridge::with_clients(Arc::new(rpc_client), mock_tpu_client());
+        let tick_driver = CountingTickDriver::default();
+
+        let signature = bridge
+            .airdrop_and_confirm(&to, 5_000, &tick_driver)
+            .unwrap();
+        assert_eq!(signature, airdrop_signature);
+
+        // The airdrop is requested once, for the recipient and amount, with the fetched blockhash
+        let airdrops = rpc_script.requests_of(RpcRequest::RequestAirdrop);
+        assert_eq!(airdrops.len(), 1);
+        assert_eq!(airdrops[0][0], json!(to.to_string()));
+        assert_eq!(airdrops[0][1], json!(5_000));
+        assert_eq!(
+            airdrops[0][2]["recentBlockhash"],
+            json!(blockhash.to_string())
+        );
+
+        // Every status poll asks about the airdrop, and each unconfirmed poll drives a tick
+        let polls = rpc_script.requests_of(RpcRequest::GetSignatureStatuses);
+        assert_eq!(polls.len(), 3);
+        for poll in &polls {
+            assert_eq!(poll[0], json!([airdrop_signature.to_string()]));
+        }
+        assert_eq!(tick_driver.ticks.load(Ordering::SeqCst), 2);
+    }
 
     #[test]
     fn test_no_fee() {
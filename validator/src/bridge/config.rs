@@ -6,6 +6,8 @@
 //! You MUST manually change these addresses before execution to match your actual
 //! Solana node endpoints.
 
+use std::time::Duration;
+
 /// Default Solana node configuration
 ///
 /// **WARNING**: These are internal network tunnel addresses and must be changed
@@ -35,4 +37,22 @@ impl MultivmConfig {
     pub fn urls() -> (String, String) {
         (Self::rpc_url(), Self::websocket_url())
     }
-}
\ No newline at end of file
+}
+
+/// Thresholds for the RPC circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive transport failures before the circuit opens
+    pub failure_threshold: u32,
+    /// How long an open circuit fails fast before probing again
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(10),
+        }
+    }
+}
@@ -0,0 +1,429 @@
+//! Error types shared by the bridge modules
+//!
+//! Every error variant has a stable `ErrorCode`, so the same failure carries
+//! the same identity through the Rust API and JSON-RPC responses. Codes are
+//! append-only: never renumber or reuse a code, add new ones at the end.
+
+use {
+    serde_json::json,
+    solana_sdk::{
+        commitment_config::CommitmentLevel, pubkey::Pubkey, signature::Signature,
+        transaction::TransactionError,
+    },
+    std::time::Duration,
+    thiserror::Error,
+};
+
+/// Stable machine-readable identity of a bridge error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    RpcError,
+    CircuitOpen,
+    BlockhashExpired,
+    ChainStalled,
+    ZeroAmount,
+    SpendingLimitExceeded,
+    RecipientNotAllowed,
+    SendFailed,
+    ConfirmationTimeout,
+    TransactionError,
+    TickFailed,
+    JwtMissing,
+    JwtEncoding,
+    BurnRecipient,
+    PerCallLimitExceeded,
+    SpendingPersistence,
+    MemoInvalidUtf8,
+    MemoInvalidStructured,
+    MemoInvalidEvmAddress,
+    MemoChainIdMismatch,
+    ConversionOverflow,
+    ConversionUnsupportedDecimals,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 22] = [
+        ErrorCode::RpcError,
+        ErrorCode::CircuitOpen,
+        ErrorCode::BlockhashExpired,
+        ErrorCode::ChainStalled,
+        ErrorCode::ZeroAmount,
+        ErrorCode::SpendingLimitExceeded,
+        ErrorCode::RecipientNotAllowed,
+        ErrorCode::SendFailed,
+        ErrorCode::ConfirmationTimeout,
+        ErrorCode::TransactionError,
+        ErrorCode::TickFailed,
+        ErrorCode::JwtMissing,
+        ErrorCode::JwtEncoding,
+        ErrorCode::BurnRecipient,
+        ErrorCode::PerCallLimitExceeded,
+        ErrorCode::SpendingPersistence,
+        ErrorCode::MemoInvalidUtf8,
+        ErrorCode::MemoInvalidStructured,
+        ErrorCode::MemoInvalidEvmAddress,
+        ErrorCode::MemoChainIdMismatch,
+        ErrorCode::ConversionOverflow,
+        ErrorCode::ConversionUnsupportedDecimals,
+    ];
+
+    /// Numeric code, also used as the JSON-RPC error code
+    pub fn code(self) -> i64 {
+        match self {
+            ErrorCode::RpcError => 1000,
+            ErrorCode::CircuitOpen => 1001,
+            ErrorCode::BlockhashExpired => 1002,
+            ErrorCode::ChainStalled => 1003,
+            ErrorCode::ZeroAmount => 1004,
+            ErrorCode::SpendingLimitExceeded => 1005,
+            ErrorCode::RecipientNotAllowed => 1006,
+            ErrorCode::SendFailed => 1007,
+            ErrorCode::ConfirmationTimeout => 1008,
+            ErrorCode::TransactionError => 1009,
+            ErrorCode::TickFailed => 1010,
+            ErrorCode::JwtMissing => 1011,
+            ErrorCode::JwtEncoding => 1012,
+            ErrorCode::BurnRecipient => 1013,
+            ErrorCode::PerCallLimitExceeded => 1014,
+            ErrorCode::SpendingPersistence => 1015,
+            ErrorCode::MemoInvalidUtf8 => 2000,
+            ErrorCode::MemoInvalidStructured => 2001,
+            ErrorCode::MemoInvalidEvmAddress => 2002,
+            ErrorCode::MemoChainIdMismatch => 2003,
+            ErrorCode::ConversionOverflow => 3000,
+            ErrorCode::ConversionUnsupportedDecimals => 3001,
+        }
+    }
+
+    /// snake_case name carried as `data.error_code` in JSON-RPC errors
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCode::RpcError => "rpc_error",
+            ErrorCode::CircuitOpen => "circuit_open",
+            ErrorCode::BlockhashExpired => "blockhash_expired",
+            ErrorCode::ChainStalled => "chain_stalled",
+            ErrorCode::ZeroAmount => "zero_amount",
+            ErrorCode::SpendingLimitExceeded => "spending_limit_exceeded",
+            ErrorCode::RecipientNotAllowed => "recipient_not_allowed",
+            ErrorCode::SendFailed => "send_failed",
+            ErrorCode::ConfirmationTimeout => "confirmation_timeout",
+            ErrorCode::TransactionError => "transaction_error",
+            ErrorCode::TickFailed => "tick_failed",
+            ErrorCode::JwtMissing => "jwt_missing",
+            ErrorCode::JwtEncoding => "jwt_encoding",
+            ErrorCode::BurnRecipient => "burn_recipient",
+            ErrorCode::PerCallLimitExceeded => "per_call_limit_exceeded",
+            ErrorCode::SpendingPersistence => "spending_persistence",
+            ErrorCode::MemoInvalidUtf8 => "memo_invalid_utf8",
+            ErrorCode::MemoInvalidStructured => "memo_invalid_structured",
+            ErrorCode::MemoInvalidEvmAddress => "memo_invalid_evm_address",
+            ErrorCode::MemoChainIdMismatch => "memo_chain_id_mismatch",
+            ErrorCode::ConversionOverflow => "conversion_overflow",
+            ErrorCode::ConversionUnsupportedDecimals => "conversion_unsupported_decimals",
+        }
+    }
+
+    /// JSON-RPC error for `message` tagged with this code
+    pub fn to_json_rpc_error(self, message: String) -> jsonrpc_core::Error {
+        jsonrpc_core::Error {
+            code: jsonrpc_core::ErrorCode::ServerError(self.code()),
+            message,
+            data: Some(json!({ "error_code": self.name() })),
+        }
+    }
+}
+
+/// Errors returned by the bridge helpers
+#[derive(Debug, Error)]
+pub enum BridgeError {
+    /// The RPC call reached the node but failed, or failed in transport
+    #[error("RPC call failed: {0}")]
+    RpcError(String),
+
+    /// Too many consecutive transport failures; calls fail fast until the cooldown ends
+    #[error("RPC circuit breaker is open, retry after {retry_after:?}")]
+    CircuitOpen { retry_after: Duration },
+
+    /// The chain moved past the transaction's last valid block height without including it
+    #[error(
+        "Transaction {signature} expired: block height {block_height} exceeded last valid block height {last_valid_block_height}"
+    )]
+    BlockhashExpired {
+        signature: Signature,
+        last_valid_block_height: u64,
+        block_height: u64,
+    },
+
+    /// The slot did not advance for the configured stall window while confirming
+    #[error("Chain stalled at slot {slot} after {attempts} attempts (last tick error: {last_tick_error:?})")]
+    ChainStalled {
+        slot: u64,
+        attempts: u32,
+        last_tick_error: Option<String>,
+    },
+
+    /// A zero-lamport transfer would only pay fees; see `TransferOptions::allow_zero_amount`
+    #[error("Refusing to send a zero-lamport transfer")]
+    ZeroAmount,
+
+    /// A reward distribution would exceed the rolling-window spending cap
+    #[error("Spending limit exceeded: requested {requested} lamports, {remaining} remaining in {window:?}")]
+    SpendingLimitExceeded {
+        requested: u64,
+        remaining: u64,
+        window: Duration,
+    },
+
+    /// The recipient is not on the spending allow-list
+    #[error("Recipient {recipient} is not on the spending allow-list")]
+    RecipientNotAllowed { recipient: Pubkey },
+
+    /// The node rejected the transaction before it was processed
+    #[error("Transaction send failed: {0}")]
+    SendFailed(String),
+
+    /// The transaction did not reach `commitment` within `max_retries` polls
+    #[error(
+        "Transaction {signature} confirmation timeout after {attempts} attempts waiting for {commitment:?}"
+    )]
+    ConfirmationTimeout {
+        signature: Signature,
+        attempts: u32,
+        commitment: CommitmentLevel,
+    },
+
+    /// The transaction was processed and failed on chain
+    #[error("Transaction failed: {0}")]
+    TransactionError(TransactionError),
+
+    /// Driving a manual tick failed
+    #[error("Tick failed: {0}")]
+    TickFailed(String),
+
+    /// The RPC client has no JWT secret for the privileged methods
+    #[error("JWT token not set")]
+    JwtMissing,
+
+    /// The JWT secret could not be turned into a token
+    #[error("Failed to create JWT token: {0}")]
+    JwtEncoding(String),
+
+    /// Funds sent to the recipient could never be spent; see `TransferOptions::allow_burn_recipient`
+    #[error("Refusing to transfer to burn address {recipient}")]
+    BurnRecipient { recipient: Pubkey },
+
+    /// A single reward distribution would exceed the per-call spending cap
+    #[error("Per-call spending limit exceeded: requested {requested} lamports, cap is {cap}")]
+    PerCallLimitExceeded { requested: u64, cap: u64 },
+
+    /// The spend history could not be written, so the spend was not authorized
+    #[error("Failed to persist spending history: {0}")]
+    SpendingPersistence(String),
+}
+
+impl BridgeError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            BridgeError::RpcError(_) => ErrorCode::RpcError,
+            BridgeError::CircuitOpen { .. } => ErrorCode::CircuitOpen,
+            BridgeError::BlockhashExpired { .. } => ErrorCode::BlockhashExpired,
+            BridgeError::ChainStalled { .. } => ErrorCode::ChainStalled,
+            BridgeError::ZeroAmount => ErrorCode::ZeroAmount,
+            BridgeError::SpendingLimitExceeded { .. } => ErrorCode::SpendingLimitExceeded,
+            BridgeError::RecipientNotAllowed { .. } => ErrorCode::RecipientNotAllowed,
+            BridgeError::SendFailed(_) => ErrorCode::SendFailed,
+            BridgeError::ConfirmationTimeout { .. } => ErrorCode::ConfirmationTimeout,
+            BridgeError::TransactionError(_) => ErrorCode::TransactionError,
+            BridgeError::TickFailed(_) => ErrorCode::TickFailed,
+            BridgeError::JwtMissing => ErrorCode::JwtMissing,
+            BridgeError::JwtEncoding(_) => ErrorCode::JwtEncoding,
+            BridgeError::BurnRecipient { .. } => ErrorCode::BurnRecipient,
+            BridgeError::PerCallLimitExceeded { .. } => ErrorCode::PerCallLimitExceeded,
+            BridgeError::SpendingPersistence(_) => ErrorCode::SpendingPersistence,
+        }
+    }
+}
+
+impl From<&BridgeError> for jsonrpc_core::Error {
+    fn from(error: &BridgeError) -> Self {
+        error.code().to_json_rpc_error(error.to_string())
+    }
+}
+
+/// Errors returned when parsing a deposit memo
+#[derive(Debug, Error)]
+pub enum MemoError {
+    #[error("Memo is not valid UTF-8")]
+    InvalidUtf8,
+
+    #[error("Invalid structured memo: {0}")]
+    InvalidStructuredMemo(String),
+
+    #[error("Invalid EVM address in memo: {0}")]
+    InvalidEvmAddress(String),
+
+    /// The memo targets a different EVM chain than the bridge serves
+    #[error("Memo chain id {actual:?} does not match expected chain id {expected}")]
+    ChainIdMismatch { expected: u64, actual: Option<u64> },
+}
+
+impl MemoError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            MemoError::InvalidUtf8 => ErrorCode::MemoInvalidUtf8,
+            MemoError::InvalidStructuredMemo(_) => ErrorCode::MemoInvalidStructured,
+            MemoError::InvalidEvmAddress(_) => ErrorCode::MemoInvalidEvmAddress,
+            MemoError::ChainIdMismatch { .. } => ErrorCode::MemoChainIdMismatch,
+        }
+    }
+}
+
+impl From<&MemoError> for jsonrpc_core::Error {
+    fn from(error: &MemoError) -> Self {
+        error.code().to_json_rpc_error(error.to_string())
+    }
+}
+
+/// Errors returned when converting amounts between Solana and EVM decimals
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConversionError {
+    /// The converted amount does not fit the target integer type
+    #[error("Amount {amount} overflows when converted")]
+    Overflow { amount: u128 },
+
+    /// The decimals differ by more than a `u128` scale factor can represent
+    #[error("Unsupported decimals: {sol_decimals} Solana, {evm_decimals} EVM")]
+    UnsupportedDecimals { sol_decimals: u8, evm_decimals: u8 },
+}
+
+impl ConversionError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ConversionError::Overflow { .. } => ErrorCode::ConversionOverflow,
+            ConversionError::UnsupportedDecimals { .. } => ErrorCode::ConversionUnsupportedDecimals,
+        }
+    }
+}
+
+impl From<&ConversionError> for jsonrpc_core::Error {
+    fn from(error: &ConversionError) -> Self {
+        error.code().to_json_rpc_error(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::collections::HashSet};
+
+    fn bridge_errors() -> Vec<BridgeError> {
+        vec![
+            BridgeError::RpcError("connection refused".to_string()),
+            BridgeError::CircuitOpen {
+                retry_after: Duration::from_secs(1),
+            },
+            BridgeError::BlockhashExpired {
+                signature: Signature::default(),
+                last_valid_block_height: 10,
+                block_height: 11,
+            },
+            BridgeError::ChainStalled {
+                slot: 5,
+                attempts: 3,
+                last_tick_error: None,
+            },
+            BridgeError::ZeroAmount,
+            BridgeError::SpendingLimitExceeded {
+                requested: 2,
+                remaining: 1,
+                window: Duration::from_secs(60),
+            },
+            BridgeError::RecipientNotAllowed {
+                recipient: Pubkey::default(),
+            },
+            BridgeError::SendFailed("blockhash not found".to_string()),
+            BridgeError::ConfirmationTimeout {
+                signature: Signature::default(),
+                attempts: 60,
+                commitment: CommitmentLevel::Confirmed,
+            },
+            BridgeError::TransactionError(TransactionError::AccountNotFound),
+            BridgeError::TickFailed("Tick channel disconnected".to_string()),
+            BridgeError::JwtMissing,
+            BridgeError::JwtEncoding("Odd number of digits".to_string()),
+            BridgeError::BurnRecipient {
+                recipient: Pubkey::default(),
+            },
+            BridgeError::PerCallLimitExceeded {
+                requested: 2,
+                cap: 1,
+            },
+            BridgeError::SpendingPersistence("disk full".to_string()),
+        ]
+    }
+
+    fn memo_errors() -> Vec<MemoError> {
+        vec![
+            MemoError::InvalidUtf8,
+            MemoError::InvalidStructuredMemo("missing to".to_string()),
+            MemoError::InvalidEvmAddress("0x12".to_string()),
+            MemoError::ChainIdMismatch {
+                expected: 1,
+                actual: None,
+            },
+        ]
+    }
+
+    fn conversion_errors() -> Vec<ConversionError> {
+        vec![
+            ConversionError::Overflow { amount: u128::MAX },
+            ConversionError::UnsupportedDecimals {
+                sol_decimals: 0,
+                evm_decimals: 255,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_codes_are_unique() {
+        let codes = ErrorCode::ALL
+            .iter()
+            .map(|c| c.code())
+            .collect::<HashSet<_>>();
+        let names = ErrorCode::ALL
+            .iter()
+            .map(|c| c.name())
+            .collect::<HashSet<_>>();
+        assert_eq!(codes.len(), ErrorCode::ALL.len());
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+
+        // Every variant maps to its own code, and every code is used
+        let mapped = bridge_errors()
+            .iter()
+            .map(BridgeError::code)
+            .chain(memo_errors().iter().map(MemoError::code))
+            .chain(conversion_errors().iter().map(ConversionError::code))
+            .collect::<Vec<_>>();
+        assert_eq!(mapped, ErrorCode::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_json_rpc_error_embeds_code() {
+        for error in bridge_errors() {
+            let rpc_error = jsonrpc_core::Error::from(&error);
+            assert_eq!(
+                rpc_error.code,
+                jsonrpc_core::ErrorCode::ServerError(error.code().code())
+            );
+            assert_eq!(rpc_error.message, error.to_string());
+            assert_eq!(
+                rpc_error.data,
+                Some(json!({ "error_code": error.code().name() }))
+            );
+        }
+        let rpc_error = jsonrpc_core::Error::from(&MemoError::InvalidUtf8);
+        assert_eq!(
+            rpc_error.data,
+            Some(json!({ "error_code": "memo_invalid_utf8" }))
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Amount conversion between Solana lamports and EVM token units
+//!
+//! Lamports have 9 decimals while the EVM token typically has 18, so amounts
+//! are rescaled by a power of ten. All math is integer-only: EVM amounts are
+//! held in a `u128`, which covers `u64::MAX` lamports at up to 29 extra
+//! decimals. Scaling down always rounds towards zero and returns the
+//! remainder as dust, so callers can account for every unit.
+//!
+//! Note: the ABI encoder and withdrawal parser that should convert through
+//! `AmountConverter` are not part of this tree, so nothing calls it yet.
+
+use crate::bridge::errors::ConversionError;
+
+/// Decimals of native SOL
+pub const SOL_DECIMALS: u8 = 9;
+
+/// An amount converted with floor rounding and the dust it left behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Converted<A, D> {
+    pub amount: A,
+    /// Source units that could not be represented in the target decimals
+    pub dust: D,
+}
+
+/// Converts between lamports and EVM units for a fixed pair of decimals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AmountConverter {
+    sol_decimals: u8,
+    evm_decimals: u8,
+    scale: u128,
+}
+
+impl AmountConverter {
+    pub fn new(sol_decimals: u8, evm_decimals: u8) -> Result<Self, ConversionError> {
+        let scale = 10u128
+            .checked_pow(u32::from(sol_decimals.abs_diff(evm_decimals)))
+            .ok_or(ConversionError::UnsupportedDecimals {
+                sol_decimals,
+                evm_decimals,
+            })?;
+        Ok(Self {
+            sol_decimals,
+            evm_decimals,
+            scale,
+        })
+    }
+
+    pub fn sol_decimals(&self) -> u8 {
+        self.sol_decimals
+    }
+
+    pub fn evm_decimals(&self) -> u8 {
+        self.evm_decimals
+    }
+
+    /// Lamports to EVM units; `dust` is in lamports
+    pub fn to_evm(&self, lamports: u64) -> Result<Converted<u128, u64>, ConversionError> {
+        let lamports = u128::from(lamports);
+        if self.evm_decimals >= self.sol_decimals {
+            let amount = lamports
+                .checked_mul(self.scale)
+                .ok_or(ConversionError::Overflow { amount: lamports })?;
+            Ok(Converted { amount, dust: 0 })
+        } else {
+            Ok(Converted {
+                amount: lamports / self.scale,
+                // Less than `scale`, which is at most 10^9 here since the source is u64
+                dust: (lamports % self.scale) as u64,
+            })
+        }
+    }
+
+    /// EVM units to lamports; `dust` is in EVM units
+    pub fn to_lamports(&self, evm_amount: u128) -> Result<Converted<u64, u128>, ConversionError> {
+        let (lamports, dust) = if self.evm_decimals >= self.sol_decimals {
+            (evm_amount / self.scale, evm_amount % self.scale)
+        } else {
+            let lamports = evm_amount
+                .checked_mul(self.scale)
+                .ok_or(ConversionError::Overflow { amount: evm_amount })?;
+            (lamports, 0)
+        };
+        let lamports = u64::try_from(lamports)
+            .map_err(|_| ConversionError::Overflow { amount: evm_amount })?;
+        Ok(Converted {
+            amount: lamports,
+            dust,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEI_PER_LAMPORT: u128 = 1_000_000_000;
+
+    #[test]
+    fn test_max_lamports() {
+        let converter = AmountConverter::new(SOL_DECIMALS, 18).unwrap();
+        let converted = converter.to_evm(u64::MAX).unwrap();
+        assert_eq!(converted.amount, u128::from(u64::MAX) * WEI_PER_LAMPORT);
+        assert_eq!(converted.dust, 0);
+        assert_eq!(
+            converter.to_lamports(converted.amount).unwrap(),
+            Converted {
+                amount: u64::MAX,
+                dust: 0,
+            }
+        );
+
+        // One lamport more than u64::MAX is rejected rather than wrapped
+        assert_eq!(
+            converter.to_lamports(converted.amount + WEI_PER_LAMPORT),
+            Err(ConversionError::Overflow {
+                amount: converted.amount + WEI_PER_LAMPORT,
+            })
+        );
+        assert!(AmountConverter::new(0, 38).is_ok());
+        assert_eq!(
+            AmountConverter::new(0, 39),
+            Err(ConversionError::UnsupportedDecimals {
+                sol_decimals: 0,
+                evm_decimals: 39,
+            })
+        );
+        assert!(matches!(
+            AmountConverter::new(0, 38).unwrap().to_evm(u64::MAX),
+            Err(ConversionError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    fn test_dust_remainder() {
+        let converter = AmountConverter::new(SOL_DECIMALS, 18).unwrap();
+        let wei = 1_500_000_000_123;
+        let converted = converter.to_lamports(wei).unwrap();
+        assert_eq!(converted.amount, 1_500);
+        assert_eq!(converted.dust, 123);
+        // Nothing is lost: converting back and adding the dust restores the input
+        assert_eq!(
+            converter.to_evm(converted.amount).unwrap().amount + converted.dust,
+            wei
+        );
+
+        // EVM token with fewer decimals than SOL leaves dust in lamports
+        let converter = AmountConverter::new(SOL_DECIMALS, 6).unwrap();
+        assert_eq!(
+            converter.to_evm(1_234_567).unwrap(),
+            Converted {
+                amount: 1_234,
+                dust: 567,
+            }
+        );
+        assert_eq!(
+            converter.to_lamports(1_234).unwrap(),
+            Converted {
+                amount: 1_234_000,
+                dust: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (sol_decimals, evm_decimals) in [(9, 18), (9, 9), (9, 6)] {
+            let converter = AmountConverter::new(sol_decimals, evm_decimals).unwrap();
+            for lamports in [0, 1_000, 999_999_999_000, u64::MAX / 1_000 * 1_000] {
+                let evm = converter.to_evm(lamports).unwrap();
+                assert_eq!(evm.dust, 0);
+                assert_eq!(
+                    converter.to_lamports(evm.amount).unwrap().amount,
+                    lamports,
+                    "{} -> {} decimals",
+                    sol_decimals,
+                    evm_decimals
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,812 @@
+//! Export of bridge transfers (system transfer + EVM memo) to flat files
+//!
+//! Blocks are fetched one slot at a time and rows are streamed to the output
+//! file, so memory use does not depend on the size of the slot range. After
+//! every chunk of `chunk_slots` slots the writer is flushed and a checkpoint is
+//! written next to the output file; an export started with `resume: true`
+//! truncates the output back to the last checkpoint and continues from there.
+//! A block that is not available yet stops the export at the last checkpoint
+//! instead of being exported as empty, so it can be resumed later.
+//!
+//! `export_transfers_with_notifier` also hands each confirmed deposit in the
+//! exported blocks to a `DedupNotifier`, so a resumed or repeated export does
+//! not notify the same deposit twice.
+
+use {
+    crate::bridge::{
+        finality::{ChainContinuityChecker, NonceRegistry},
+        notify::{DedupNotifier, NoopNotifier},
+        util::{
+            decode_block_transaction, deposits_in_block, is_block_unavailable, is_slot_skipped,
+            parse_transfer_transaction_versioned,
+        },
+    },
+    log::{debug, info},
+    serde::{Deserialize, Serialize},
+    solana_client::rpc_client::RpcClient,
+    solana_rpc_client_api::config::RpcBlockConfig,
+    solana_sdk::commitment_config::CommitmentConfig,
+    solana_transaction_status_client_types::{
+        TransactionDetails, UiConfirmedBlock, UiTransactionEncoding,
+    },
+    std::{
+        fs::{self, File, OpenOptions},
+        io::{BufWriter, Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+    },
+};
+
+/// Output file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma separated values with a header row
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+/// Options controlling an export run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Number of slots processed between flushes and checkpoints
+    pub chunk_slots: u64,
+    /// Continue from the checkpoint left by an interrupted run of the same range
+    pub resume: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            chunk_slots: 1_000,
+            resume: false,
+        }
+    }
+}
+
+/// Totals for a finished export, including rows written by resumed runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub rows: u64,
+    pub resumed_from: Option<u64>,
+}
+
+/// One exported transfer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransferRow {
+    pub slot: u64,
+    pub signature: String,
+    pub from: String,
+    pub to: String,
+    pub lamports: u64,
+    pub evm_address: String,
+    pub block_time: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    from_slot: u64,
+    to_slot: u64,
+    next_slot: u64,
+    rows: u64,
+    bytes_written: u64,
+}
+
+const CSV_HEADER: &str = "slot,signature,from,to,lamports,evm_address,block_time\n";
+
+/// Export all transfers with an EVM memo in `from_slot..=to_slot` to `out_path`
+///
+/// Skipped slots are treated as empty. Transactions that failed on chain are not
+/// exported, nor are v0 transactions whose lookup-table addresses are missing
+/// from `meta`. If a block in the range is not
+/// available yet the export fails without advancing the checkpoint past it.
+pub fn export_transfers(
+    rpc_client: &RpcClient,
+    from_slot: u64,
+    to_slot: u64,
+    format: ExportFormat,
+    out_path: &Path,
+    options: &ExportOptions,
+) -> Result<ExportSummary, Box<dyn std::error::Error + Send + Sync>> {
+    export_transfers_with_notifier(
+        rpc_client,
+        from_slot,
+        to_slot,
+        format,
+        out_path,
+        options,
+        &DedupNotifier::new(NoopNotifier),
+        None,
+        None,
+    )
+}
+
+/// Same as `export_transfers`, and notifies each deposit in the exported
+/// blocks that `notifier` has not seen yet
+///
+/// Only transactions with a successful status (`meta`) count as confirmed
+/// deposits; a failing notifier is logged and does not stop the export. With
+/// `nonces`, deposits reusing an (evm_address, nonce) pair are notified with
+/// `duplicate_of` set, as in `deposits_in_block`.
+///
+/// With `continuity`, every fetched block is first checked to chain onto the
+/// previous one, and deposits are only notified while the checker allows
+/// emission; rows are exported either way. Blocks at or below the checker's
+/// last observed slot (e.g. after resuming) are not checked again.
+#[allow(clippy::too_many_arguments)]
+pub fn export_transfers_with_notifier(
+    rpc_client: &RpcClient,
+    from_slot: u64,
+    to_slot: u64,
+    format: ExportFormat,
+    out_path: &Path,
+    options: &ExportOptions,
+    notifier: &DedupNotifier,
+    mut nonces: Option<&mut NonceRegistry>,
+    mut continuity: Option<&mut ChainContinuityChecker>,
+) -> Result<ExportSummary, Box<dyn std::error::Error + Send + Sync>> {
+    if from_slot > to_slot {
+        return Err(format!("Invalid slot range {from_slot}..={to_slot}").into());
+    }
+    let checkpoint_path = checkpoint_path(out_path);
+
+    let checkpoint = if options.resume {
+        read_checkpoint(&checkpoint_path)?
+            .filter(|c| c.from_slot == from_slot && c.to_slot == to_slot)
+    } else {
+        None
+    };
+    let resumed_from = checkpoint.as_ref().map(|c| c.next_slot);
+
+    let (mut file, mut checkpoint) = match checkpoint {
+        Some(checkpoint) => {
+            info!(
+                "Resuming export of {}..={} at slot {}",
+                from_slot, to_slot, checkpoint.next_slot
+            );
+            // Drop anything written after the last checkpoint
+            let file = OpenOptions::new().write(true).open(out_path)?;
+            file.set_len(checkpoint.bytes_written)?;
+            (file, checkpoint)
+        }
+        None => {
+            let mut file = File::create(out_path)?;
+            let mut bytes_written = 0;
+            if format == ExportFormat::Csv {
+                file.write_all(CSV_HEADER.as_bytes())?;
+                bytes_written = CSV_HEADER.len() as u64;
+            }
+            (
+                file,
+                Checkpoint {
+                    from_slot,
+                    to_slot,
+                    next_slot: from_slot,
+                    rows: 0,
+                    bytes_written,
+                },
+            )
+        }
+    };
+
+    file.seek(SeekFrom::End(0))?;
+    let mut writer = BufWriter::new(file);
+    let chunk_slots = options.chunk_slots.max(1);
+
+    while checkpoint.next_slot <= to_slot {
+        let chunk_end = checkpoint
+            .next_slot
+            .saturating_add(chunk_slots - 1)
+            .min(to_slot);
+        for slot in checkpoint.next_slot..=chunk_end {
+            let Some(block) = fetch_block(rpc_client, slot)? else {
+                continue;
+            };
+            for row in transfer_rows(slot, &block) {
+                let line = format_row(&row, format)?;
+                writer.write_all(line.as_bytes())?;
+                checkpoint.rows += 1;
+                checkpoint.bytes_written += line.len() as u64;
+            }
+            let can_emit = match continuity.as_deref_mut() {
+                Some(checker) => {
+                    if checker.last_slot() < Some(slot) {
+                        checker.observe(rpc_client, slot, &block)?;
+                    }
+                    checker.can_emit()
+                }
+                None => true,
+            };
+            if can_emit {
+                deposits_in_block(slot, &block, notifier, nonces.as_deref_mut());
+            }
+        }
+        writer.flush()?;
+        checkpoint.next_slot = chunk_end + 1;
+        write_checkpoint(&checkpoint_path, &checkpoint)?;
+        debug!(
+            "Exported slots up to {} ({} rows)",
+            chunk_end, checkpoint.rows
+        );
+    }
+
+    Ok(ExportSummary {
+        rows: checkpoint.rows,
+        resumed_from,
+    })
+}
+
+fn checkpoint_path(out_path: &Path) -> PathBuf {
+    let mut path = out_path.as_os_str().to_owned();
+    path.push(".checkpoint");
+    PathBuf::from(path)
+}
+
+fn read_checkpoint(
+    path: &Path,
+) -> Result<Option<Checkpoint>, Box<dyn std::error::Error + Send + Sync>> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_checkpoint(
+    path: &Path,
+    checkpoint: &Checkpoint,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Write then rename so a crash never leaves a torn checkpoint
+    let tmp_path = path.with_extension("checkpoint.tmp");
+    fs::write(&tmp_path, serde_json::to_vec(checkpoint)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+/// Fetch a block with full transactions; `None` if the slot was skipped
+///
+/// A block that is not available (yet) is an error, not an empty slot.
+pub(crate) fn fetch_block(
+    rpc_client: &RpcClient,
+    slot: u64,
+) -> Result<Option<UiConfirmedBlock>, Box<dyn std::error::Error + Send + Sync>> {
+    let config = RpcBlockConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        transaction_details: Some(TransactionDetails::Full),
+        rewards: Some(false),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    match rpc_client.get_block_with_config(slot, config) {
+        Ok(block) => Ok(Some(block)),
+        Err(e) if is_slot_skipped(&e) => {
+            debug!("Skipping slot {}: {}", slot, e);
+            Ok(None)
+        }
+        Err(e) if is_block_unavailable(&e) => {
+            Err(format!("Block at slot {slot} is not available yet: {e}").into())
+        }
+        Err(e) => Err(format!("Failed to get block at slot {slot}: {e}").into()),
+    }
+}
+
+fn transfer_rows(slot: u64, block: &UiConfirmedBlock) -> Vec<TransferRow> {
+    block
+        .transactions
+        .iter()
+        .flatten()
+        .filter(|tx| tx.meta.as_ref().map_or(true, |meta| meta.err.is_none()))
+        .filter_map(decode_block_transaction)
+        .filter_map(|(tx, loaded_addresses)| {
+            let (from, to, lamports, evm_address) =
+                parse_transfer_transaction_versioned(&tx, loaded_addresses.as_ref()).ok()??;
+            Some(TransferRow {
+                slot,
+                signature: tx.signatures.first()?.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+                lamports,
+                evm_address,
+                block_time: block.block_time,
+            })
+        })
+        .collect()
+}
+
+fn format_row(
+    row: &TransferRow,
+    format: ExportFormat,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(match format {
+        // Every field is base58, hex or numeric, so no quoting is needed
+        ExportFormat::Csv => format!(
+            "{},{},{},{},{},{},{}\n",
+            row.slot,
+            row.signature,
+            row.from,
+            row.to,
+            row.lamports,
+            row.evm_address,
+            row.block_time.map(|t| t.to_string()).unwrap_or_default(),
+        ),
+        ExportFormat::JsonLines => format!("{}\n", serde_json::to_string(row)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::bridge::{
+            finality::ChainAnomaly, index::DepositEvent, notify::ConfirmationNotifier,
+            util::create_transfer_with_evm_memo,
+        },
+        base64::{prelude::BASE64_STANDARD, Engine},
+        solana_client::{
+            rpc_client::RpcClientConfig,
+            rpc_sender::{RpcSender, RpcTransportStats},
+        },
+        solana_rpc_client_api::{
+            client_error::{ErrorKind as ClientErrorKind, Result as ClientResult},
+            custom_error::{
+                JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE, JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+            },
+            request::{RpcError, RpcRequest, RpcResponseErrorData},
+        },
+        solana_sdk::{
+            hash::Hash,
+            pubkey::Pubkey,
+            signature::{Keypair, Signature, Signer},
+        },
+        solana_transaction_status_client_types::{
+            option_serializer::OptionSerializer, EncodedTransaction,
+            EncodedTransactionWithStatusMeta, TransactionBinaryEncoding, UiTransactionStatusMeta,
+        },
+        std::sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    const SLOTS: u64 = 300;
+    const EVM_ADDRESS: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+    /// A chain where every 7th slot is skipped and every 3rd slot holds one
+    /// bridge transfer; can fail once in transport at a chosen slot, can
+    /// report blocks from a chosen slot on as not available yet, and can serve
+    /// one block whose parent hash does not chain
+    struct MockChain {
+        payer: Keypair,
+        vault: Pubkey,
+        fail_at_slot: AtomicU64,
+        available_below_slot: AtomicU64,
+        broken_parent_at_slot: AtomicU64,
+    }
+
+    impl MockChain {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                payer: Keypair::new(),
+                vault: Pubkey::new_unique(),
+                fail_at_slot: AtomicU64::new(u64::MAX),
+                available_below_slot: AtomicU64::new(u64::MAX),
+                broken_parent_at_slot: AtomicU64::new(u64::MAX),
+            })
+        }
+
+        fn blockhash(slot: u64) -> Hash {
+            Hash::new_from_array([slot as u8; 32])
+        }
+
+        /// The closest earlier slot that was not skipped
+        fn parent_slot(slot: u64) -> u64 {
+            (0..slot).rev().find(|s| s % 7 != 0).unwrap_or(0)
+        }
+
+        fn expected_rows() -> u64 {
+            (0..SLOTS).filter(|s| s % 7 != 0 && s % 3 == 0).count() as u64
+        }
+
+        fn block(&self, slot: u64) -> UiConfirmedBlock {
+            let transactions = (slot % 3 == 0).then(|| {
+                let tx = create_transfer_with_evm_memo(
+                    &self.payer,
+                    &self.vault,
+                    1_000 + slot,
+                    EVM_ADDRESS,
+                    Hash::new_from_array([slot as u8; 32]),
+                )
+                .unwrap();
+                vec![EncodedTransactionWithStatusMeta {
+                    transaction: EncodedTransaction::Binary(
+                        BASE64_STANDARD.encode(bincode::serialize(&tx).unwrap()),
+                        TransactionBinaryEncoding::Base64,
+                    ),
+                    meta: Some(UiTransactionStatusMeta {
+                        err: None,
+                        status: Ok(()),
+                        fee: 5_000,
+                        pre_balances: vec![],
+                        post_balances: vec![],
+                        inner_instructions: OptionSerializer::None,
+                        log_messages: OptionSerializer::None,
+                        pre_token_balances: OptionSerializer::None,
+                        post_token_balances: OptionSerializer::None,
+                        rewards: OptionSerializer::None,
+                        loaded_addresses: OptionSerializer::Skip,
+                        return_data: OptionSerializer::Skip,
+                        compute_units_consumed: OptionSerializer::Skip,
+                    }),
+                    version: None,
+                }]
+            });
+            let parent_slot = Self::parent_slot(slot);
+            let previous_blockhash = if slot == self.broken_parent_at_slot.load(Ordering::SeqCst) {
+                Hash::new_unique()
+            } else {
+                Self::blockhash(parent_slot)
+            };
+            UiConfirmedBlock {
+                previous_blockhash: previous_blockhash.to_string(),
+                blockhash: Self::blockhash(slot).to_string(),
+                parent_slot,
+                transactions: Some(transactions.unwrap_or_default()),
+                signatures: None,
+                rewards: None,
+                num_reward_partitions: None,
+                block_time: Some(1_700_000_000 + slot as i64),
+                block_height: Some(slot),
+            }
+        }
+    }
+
+    struct MockChainSender(Arc<MockChain>);
+
+    #[async_trait::async_trait]
+    impl RpcSender for MockChainSender {
+        async fn send(
+            &self,
+            request: RpcRequest,
+            params: serde_json::Value,
+        ) -> ClientResult<serde_json::Value> {
+            if request == RpcRequest::GetBlocks {
+                let start_slot = params[0].as_u64().unwrap();
+                let end_slot = params[1].as_u64().unwrap();
+                let slots: Vec<_> = (start_slot..=end_slot).filter(|s| s % 7 != 0).collect();
+                return Ok(serde_json::json!(slots));
+            }
+            assert_eq!(request, RpcRequest::GetBlock);
+            let slot = params[0].as_u64().unwrap();
+            let chain = &self.0;
+            if chain
+                .fail_at_slot
+                .compare_exchange(slot, u64::MAX, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "connection reset",
+                )
+                .into());
+            }
+            if slot % 7 == 0 {
+                return Err(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                    code: JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+                    message: format!("Slot {slot} was skipped"),
+                    data: RpcResponseErrorData::Empty,
+                })
+                .into());
+            }
+            if slot >= chain.available_below_slot.load(Ordering::SeqCst) {
+                return Err(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                    code: JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                    message: format!("Block not available for slot {slot}"),
+                    data: RpcResponseErrorData::Empty,
+                })
+                .into());
+            }
+            Ok(serde_json::to_value(chain.block(slot)).unwrap())
+        }
+
+        async fn send_with_auth_token(
+            &self,
+            request: RpcRequest,
+            params: serde_json::Value,
+            _auth_token: String,
+        ) -> ClientResult<serde_json::Value> {
+            self.send(request, params).await
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "mock-chain".to_string()
+        }
+    }
+
+    fn chain_client(chain: &Arc<MockChain>) -> RpcClient {
+        RpcClient::new_sender(
+            MockChainSender(chain.clone()),
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+        )
+    }
+
+    fn temp_out_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "bridge-export-{}-{}",
+            std::process::id(),
+            Pubkey::new_unique()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn test_export_row_counts_and_determinism() {
+        let chain = MockChain::new();
+        let rpc_client = chain_client(&chain);
+        let options = ExportOptions {
+            chunk_slots: 32,
+            resume: false,
+        };
+
+        let csv_path = temp_out_path("transfers.csv");
+        let summary = export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &csv_path,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(summary.rows, MockChain::expected_rows());
+        let csv = fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv.lines().count() as u64, summary.rows + 1);
+        assert!(csv.starts_with(CSV_HEADER));
+        assert!(csv.contains(&format!(",{},", chain.vault)));
+
+        let again_path = temp_out_path("transfers.csv");
+        export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &again_path,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(&again_path).unwrap(), csv);
+
+        let jsonl_path = temp_out_path("transfers.jsonl");
+        export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::JsonLines,
+            &jsonl_path,
+            &options,
+        )
+        .unwrap();
+        let rows = fs::read_to_string(&jsonl_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(rows.len() as u64, MockChain::expected_rows());
+        assert_eq!(rows[0]["slot"], 3);
+        assert_eq!(rows[0]["from"], chain.payer.pubkey().to_string());
+        assert_eq!(rows[0]["evm_address"], EVM_ADDRESS);
+    }
+
+    struct RecordingNotifier(Arc<Mutex<Vec<Signature>>>);
+
+    impl ConfirmationNotifier for RecordingNotifier {
+        fn notify(
+            &self,
+            deposit: &DepositEvent,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.0.lock().unwrap().push(deposit.signature);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_export_notifies_each_deposit_once() {
+        let chain = MockChain::new();
+        let rpc_client = chain_client(&chain);
+        let options = ExportOptions {
+            chunk_slots: 25,
+            resume: true,
+        };
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let notifier = DedupNotifier::new(RecordingNotifier(notified.clone()));
+
+        // Interrupted and resumed: slots before the failure are scanned twice
+        let out_path = temp_out_path("notified.csv");
+        chain.fail_at_slot.store(160, Ordering::SeqCst);
+        for _ in 0..2 {
+            let _ = export_transfers_with_notifier(
+                &rpc_client,
+                0,
+                SLOTS - 1,
+                ExportFormat::Csv,
+                &out_path,
+                &options,
+                &notifier,
+                None,
+                None,
+            );
+        }
+        let notified = notified.lock().unwrap().clone();
+        assert_eq!(notified.len() as u64, MockChain::expected_rows());
+        let csv = fs::read_to_string(&out_path).unwrap();
+        assert!(notified
+            .iter()
+            .all(|signature| csv.contains(&signature.to_string())));
+    }
+
+    #[test]
+    fn test_export_pauses_emission_on_chain_anomaly() {
+        let chain = MockChain::new();
+        chain.broken_parent_at_slot.store(150, Ordering::SeqCst);
+        let rpc_client = chain_client(&chain);
+        let options = ExportOptions {
+            chunk_slots: 25,
+            resume: false,
+        };
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let notifier = DedupNotifier::new(RecordingNotifier(notified.clone()));
+        let state_path = temp_out_path("continuity.json");
+        let mut checker = ChainContinuityChecker::open(&state_path).unwrap();
+        let deposits_below = |slot: u64| (0..slot).filter(|s| s % 7 != 0 && s % 3 == 0).count();
+
+        // Skipped slots pass; the broken block pauses emission but not the export
+        let summary = export_transfers_with_notifier(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &temp_out_path("paused.csv"),
+            &options,
+            &notifier,
+            None,
+            Some(&mut checker),
+        )
+        .unwrap();
+        assert_eq!(summary.rows, MockChain::expected_rows());
+        assert!(matches!(
+            checker.unacknowledged(),
+            Some(ChainAnomaly::ParentHashMismatch { slot: 150, .. })
+        ));
+        assert_eq!(notified.lock().unwrap().len(), deposits_below(150));
+
+        // Acknowledged: a rescan emits the held back deposits without checking
+        // the already observed blocks again
+        checker.acknowledge().unwrap();
+        export_transfers_with_notifier(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &temp_out_path("resumed.csv"),
+            &options,
+            &notifier,
+            None,
+            Some(&mut checker),
+        )
+        .unwrap();
+        assert!(checker.can_emit());
+        assert_eq!(
+            notified.lock().unwrap().len() as u64,
+            MockChain::expected_rows()
+        );
+    }
+
+    #[test]
+    fn test_export_resume() {
+        let chain = MockChain::new();
+        let rpc_client = chain_client(&chain);
+        let options = ExportOptions {
+            chunk_slots: 25,
+            resume: true,
+        };
+
+        let full_path = temp_out_path("full.csv");
+        export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &full_path,
+            &options,
+        )
+        .unwrap();
+
+        // Interrupt in the middle of a chunk, then resume
+        let out_path = temp_out_path("resumed.csv");
+        chain.fail_at_slot.store(160, Ordering::SeqCst);
+        assert!(export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &out_path,
+            &options,
+        )
+        .is_err());
+
+        let summary = export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &out_path,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(summary.resumed_from, Some(150));
+        assert_eq!(summary.rows, MockChain::expected_rows());
+        assert_eq!(
+            fs::read_to_string(&out_path).unwrap(),
+            fs::read_to_string(&full_path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_export_stops_at_unavailable_block() {
+        let chain = MockChain::new();
+        let rpc_client = chain_client(&chain);
+        let options = ExportOptions {
+            chunk_slots: 25,
+            resume: true,
+        };
+
+        let full_path = temp_out_path("full.csv");
+        export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &full_path,
+            &options,
+        )
+        .unwrap();
+
+        // The block at slot 160 has not been produced yet: the export stops
+        // at the start of its chunk instead of exporting it as empty
+        let out_path = temp_out_path("partial.csv");
+        chain.available_below_slot.store(160, Ordering::SeqCst);
+        assert!(export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &out_path,
+            &options,
+        )
+        .is_err());
+        let checkpoint = read_checkpoint(&checkpoint_path(&out_path))
+            .unwrap()
+            .unwrap();
+        assert_eq!(checkpoint.next_slot, 150);
+
+        chain.available_below_slot.store(u64::MAX, Ordering::SeqCst);
+        let summary = export_transfers(
+            &rpc_client,
+            0,
+            SLOTS - 1,
+            ExportFormat::Csv,
+            &out_path,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(summary.resumed_from, Some(150));
+        assert_eq!(summary.rows, MockChain::expected_rows());
+        assert_eq!(
+            fs::read_to_string(&out_path).unwrap(),
+            fs::read_to_string(&full_path).unwrap()
+        );
+    }
+}
@@ -0,0 +1,664 @@
+//! Chain continuity and replay checks for the block scanner
+//!
+//! Reorgs are impossible on the private chain, so a block whose parent does not
+//! match the previously scanned block means ledger corruption. The checker
+//! reports such blocks as a `ChainAnomaly` and pauses deposit emission until
+//! the anomaly is acknowledged. Its state is persisted so a restart neither
+//! forgets the last seen block nor silently resumes emission.
+//!
+//! The export walker (`export_transfers_with_notifier`) observes every block it
+//! fetches when given a checker and only emits deposits while `can_emit` holds.
+//! `multivm-bridge finality acknowledge` resumes emission after an anomaly.
+//!
+//! `NonceRegistry` flags deposits that reuse an (evm_address, nonce) pair so a
+//! relayer does not credit the same structured memo twice. `deposits_in_block`,
+//! `recent_deposits_with_notifier` and the export walker consult it when given
+//! one.
+
+use {
+    crate::bridge::index::DepositEvent,
+    log::{error, info, warn},
+    serde::{Deserialize, Serialize},
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::{commitment_config::CommitmentConfig, signature::Signature},
+    solana_transaction_status_client_types::UiConfirmedBlock,
+    std::{
+        collections::{BTreeMap, HashMap},
+        fs::{self, OpenOptions},
+        io::{BufRead, BufReader, Write},
+        path::{Path, PathBuf},
+        str::FromStr,
+    },
+};
+
+const NONCE_REGISTRY_FILE: &str = "nonces.json";
+const NONCE_ARCHIVE_FILE: &str = "nonces-archive.jsonl";
+
+/// Source of the list of slots that produced a block
+pub trait BlockListSource {
+    /// Slots in `start_slot..=end_slot` that have a block
+    fn get_blocks(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<Vec<u64>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl BlockListSource for RpcClient {
+    fn get_blocks(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<Vec<u64>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(RpcClient::get_blocks_with_commitment(
+            self,
+            start_slot,
+            Some(end_slot),
+            CommitmentConfig::confirmed(),
+        )?)
+    }
+}
+
+/// A break in the chain of scanned blocks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChainAnomaly {
+    /// A block at or below the last scanned slot was observed
+    SlotRegression { slot: u64, last_slot: u64 },
+    /// The block's parent is not the last scanned block
+    ParentSlotMismatch {
+        slot: u64,
+        expected_parent_slot: u64,
+        parent_slot: u64,
+    },
+    /// The block's `previous_blockhash` is not the last scanned blockhash
+    ParentHashMismatch {
+        slot: u64,
+        expected_blockhash: String,
+        previous_blockhash: String,
+    },
+    /// Slots between the last scanned block and this one are not skipped
+    MissedBlocks { slot: u64, missed_slots: Vec<u64> },
+}
+
+/// Callback invoked once for every detected anomaly
+pub type AnomalyCallback = Box<dyn Fn(&ChainAnomaly) + Send + Sync>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ContinuityState {
+    last_slot: Option<u64>,
+    last_blockhash: Option<String>,
+    unacknowledged: Option<ChainAnomaly>,
+}
+
+/// Verifies that every scanned block chains onto the previous one
+pub struct ChainContinuityChecker {
+    path: PathBuf,
+    state: ContinuityState,
+    on_anomaly: Option<AnomalyCallback>,
+}
+
+impl ChainContinuityChecker {
+    /// Open the checker state stored at `path`; a missing file starts fresh
+    pub fn open(path: &Path) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let state = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => ContinuityState::default(),
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(anomaly) = &state.unacknowledged {
+            warn!(
+                "Deposit emission paused by unacknowledged chain anomaly: {:?}",
+                anomaly
+            );
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            state,
+            on_anomaly: None,
+        })
+    }
+
+    /// Register the alert callback fired for every anomaly
+    pub fn on_anomaly(&mut self, callback: AnomalyCallback) {
+        self.on_anomaly = Some(callback);
+    }
+
+    /// Whether deposits may be emitted; `false` until an anomaly is acknowledged
+    pub fn can_emit(&self) -> bool {
+        self.state.unacknowledged.is_none()
+    }
+
+    /// The last observed slot, if any
+    pub fn last_slot(&self) -> Option<u64> {
+        self.state.last_slot
+    }
+
+    /// The anomaly currently pausing emission, if any
+    pub fn unacknowledged(&self) -> Option<&ChainAnomaly> {
+        self.state.unacknowledged.as_ref()
+    }
+
+    /// Resume emission after an operator has investigated the anomaly
+    pub fn acknowledge(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(anomaly) = self.state.unacknowledged.take() {
+            info!(
+                "Chain anomaly acknowledged, resuming emission: {:?}",
+                anomaly
+            );
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    /// Check `block` at `slot` against the last scanned block and record it
+    ///
+    /// Slots between the two blocks are accepted only if `blocks` reports them
+    /// as skipped. The block becomes the new reference even when an anomaly is
+    /// reported, so one break is reported once.
+    pub fn observe(
+        &mut self,
+        blocks: &dyn BlockListSource,
+        slot: u64,
+        block: &UiConfirmedBlock,
+    ) -> Result<Option<ChainAnomaly>, Box<dyn std::error::Error + Send + Sync>> {
+        let anomaly = match (self.state.last_slot, self.state.last_blockhash.as_ref()) {
+            (Some(last_slot), Some(last_blockhash)) => {
+                self.check(blocks, last_slot, last_blockhash, slot, block)?
+            }
+            _ => None,
+        };
+
+        if let Some(anomaly) = &anomaly {
+            error!("Chain anomaly detected at slot {}: {:?}", slot, anomaly);
+            if let Some(callback) = &self.on_anomaly {
+                callback(anomaly);
+            }
+            if self.state.unacknowledged.is_none() {
+                self.state.unacknowledged = Some(anomaly.clone());
+            }
+        }
+        self.state.last_slot = Some(slot);
+        self.state.last_blockhash = Some(block.blockhash.clone());
+        self.persist()?;
+        Ok(anomaly)
+    }
+
+    fn check(
+        &self,
+        blocks: &dyn BlockListSource,
+        last_slot: u64,
+        last_blockhash: &str,
+        slot: u64,
+        block: &UiConfirmedBlock,
+    ) -> Result<Option<ChainAnomaly>, Box<dyn std::error::Error + Send + Sync>> {
+        if slot <= last_slot {
+            return Ok(Some(ChainAnomaly::SlotRegression { slot, last_slot }));
+        }
+        if block.parent_slot != last_slot {
+            return Ok(Some(ChainAnomaly::ParentSlotMismatch {
+                slot,
+                expected_parent_slot: last_slot,
+                parent_slot: block.parent_slot,
+            }));
+        }
+        if block.previous_blockhash != last_blockhash {
+            return Ok(Some(ChainAnomaly::ParentHashMismatch {
+                slot,
+                expected_blockhash: last_blockhash.to_string(),
+                previous_blockhash: block.previous_blockhash.clone(),
+            }));
+        }
+        if slot > last_slot + 1 {
+            let missed_slots = blocks.get_blocks(last_slot + 1, slot - 1)?;
+            if !missed_slots.is_empty() {
+                return Ok(Some(ChainAnomaly::MissedBlocks { slot, missed_slots }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn persist(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Write then rename so a crash never leaves a torn state file
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(&self.state)?)?;
+        fs::rename(tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Bounds applied to a `NonceRegistry`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceRegistryConfig {
+    /// Nonces kept in memory per address before the lowest are compacted into
+    /// the address's used ranges
+    pub recent_window: usize,
+}
+
+impl Default for NonceRegistryConfig {
+    fn default() -> Self {
+        Self {
+            recent_window: 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct AddressNonces {
+    /// Compacted nonces as inclusive `start -> end` ranges; every nonce in a
+    /// range was used, and its signature is in the archive
+    compacted: BTreeMap<u64, u64>,
+    /// Nonce -> signature of the deposit that used it
+    recent: BTreeMap<u64, String>,
+}
+
+impl AddressNonces {
+    fn is_compacted(&self, nonce: u64) -> bool {
+        self.compacted
+            .range(..=nonce)
+            .next_back()
+            .is_some_and(|(_, end)| nonce <= *end)
+    }
+
+    fn compact(&mut self, nonce: u64) {
+        let mut start = nonce;
+        let mut end = nonce;
+        if let Some((&below_start, &below_end)) = self.compacted.range(..nonce).next_back() {
+            if below_end.checked_add(1) == Some(nonce) {
+                start = below_start;
+            }
+        }
+        if let Some(above_end) = nonce
+            .checked_add(1)
+            .and_then(|above| self.compacted.remove(&above))
+        {
+            end = above_end;
+        }
+        self.compacted.insert(start, end);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedNonce {
+    address: String,
+    nonce: u64,
+    signature: String,
+}
+
+/// Persisted record of the (evm_address, nonce) pairs already emitted
+///
+/// Only the `recent_window` highest nonces of each address are kept with their
+/// signatures. Older ones are appended to an archive file and folded into
+/// ranges of used nonces, so memory grows with the gaps between compacted
+/// nonces rather than their number. A nonce that was never seen is never
+/// flagged, however far below the compacted ranges it is; the archive is only
+/// read to name the deposit a duplicate collides with.
+pub struct NonceRegistry {
+    path: PathBuf,
+    archive_path: PathBuf,
+    config: NonceRegistryConfig,
+    addresses: HashMap<String, AddressNonces>,
+}
+
+impl NonceRegistry {
+    /// Open the registry stored in `dir`; a missing file starts empty
+    pub fn open(
+        dir: &Path,
+        config: NonceRegistryConfig,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = dir.join(NONCE_REGISTRY_FILE);
+        let addresses = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path,
+            archive_path: dir.join(NONCE_ARCHIVE_FILE),
+            config,
+            addresses,
+        })
+    }
+
+    /// Record `deposit`'s nonce, or flag it through `duplicate_of` if it was used
+    ///
+    /// Legacy deposits without a nonce pass through untouched. Checking the same
+    /// deposit again (e.g. after a rescan) does not flag it.
+    pub fn check(
+        &mut self,
+        mut deposit: DepositEvent,
+    ) -> Result<DepositEvent, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(nonce) = deposit.nonce else {
+            return Ok(deposit);
+        };
+        let signature = deposit.signature.to_string();
+        let address = deposit.evm_address.to_lowercase();
+        let nonces = self.addresses.entry(address.clone()).or_default();
+
+        let previous = match nonces.recent.get(&nonce) {
+            Some(previous) => Some(previous.clone()),
+            None if nonces.is_compacted(nonce) => {
+                Some(read_archived(&self.archive_path, &address, nonce)?)
+            }
+            None => None,
+        };
+        if let Some(previous) = previous {
+            if previous != signature {
+                warn!(
+                    "Duplicate deposit nonce {} for {}: {} already used by {}",
+                    nonce, deposit.evm_address, deposit.signature, previous
+                );
+                deposit.duplicate_of = Some(Signature::from_str(&previous)?);
+            }
+            return Ok(deposit);
+        }
+
+        nonces.recent.insert(nonce, signature);
+        let mut evicted = Vec::new();
+        while nonces.recent.len() > self.config.recent_window {
+            if let Some((lowest, signature)) = nonces.recent.pop_first() {
+                nonces.compact(lowest);
+                evicted.push(ArchivedNonce {
+                    address: address.clone(),
+                    nonce: lowest,
+                    signature,
+                });
+            }
+        }
+        // Archive first: after a crash a nonce may be both archived and recent,
+        // but never compacted without its signature on disk
+        self.archive(&evicted)?;
+        self.persist()?;
+        Ok(deposit)
+    }
+
+    fn archive(
+        &self,
+        entries: &[ArchivedNonce],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut lines = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut lines, entry)?;
+            lines.push(b'\n');
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.archive_path)?;
+        file.write_all(&lines)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn persist(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Write then rename so a crash never leaves a torn registry file
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(&self.addresses)?)?;
+        fs::rename(tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Signature of the deposit that used a compacted nonce
+fn read_archived(
+    archive_path: &Path,
+    address: &str,
+    nonce: u64,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let file = fs::File::open(archive_path)?;
+    for line in BufReader::new(file).lines() {
+        let entry: ArchivedNonce = serde_json::from_str(&line?)?;
+        if entry.address == address && entry.nonce == nonce {
+            return Ok(entry.signature);
+        }
+    }
+    error!(
+        "Nonce {} for {} is compacted but missing from {}",
+        nonce,
+        address,
+        archive_path.display()
+    );
+    Err(format!("nonce {nonce} for {address} missing from the nonce archive").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{hash::Hash, pubkey::Pubkey},
+        std::sync::{Arc, Mutex},
+    };
+
+    /// Reports every slot in `produced` as having a block
+    struct ProducedSlots(Vec<u64>);
+
+    impl BlockListSource for ProducedSlots {
+        fn get_blocks(
+            &self,
+            start_slot: u64,
+            end_slot: u64,
+        ) -> Result<Vec<u64>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(self
+                .0
+                .iter()
+                .copied()
+                .filter(|slot| (start_slot..=end_slot).contains(slot))
+                .collect())
+        }
+    }
+
+    fn blockhash(slot: u64) -> String {
+        Hash::new_from_array([slot as u8; 32]).to_string()
+    }
+
+    fn block(slot: u64, parent_slot: u64) -> UiConfirmedBlock {
+        UiConfirmedBlock {
+            previous_blockhash: blockhash(parent_slot),
+            blockhash: blockhash(slot),
+            parent_slot,
+            transactions: None,
+            signatures: None,
+            rewards: None,
+            num_reward_partitions: None,
+            block_time: None,
+            block_height: Some(slot),
+        }
+    }
+
+    fn checker_with_alerts(path: &Path) -> (ChainContinuityChecker, Arc<Mutex<Vec<ChainAnomaly>>>) {
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let mut checker = ChainContinuityChecker::open(path).unwrap();
+        let sink = alerts.clone();
+        checker.on_anomaly(Box::new(move |anomaly| {
+            sink.lock().unwrap().push(anomaly.clone())
+        }));
+        (checker, alerts)
+    }
+
+    #[test]
+    fn test_legitimate_skips_pass() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut checker, alerts) = checker_with_alerts(&dir.path().join("continuity.json"));
+        let produced = ProducedSlots(vec![1, 2, 5, 6]);
+
+        for (slot, parent_slot) in [(1, 0), (2, 1), (5, 2), (6, 5)] {
+            let anomaly = checker
+                .observe(&produced, slot, &block(slot, parent_slot))
+                .unwrap();
+            assert_eq!(anomaly, None);
+        }
+        assert!(alerts.lock().unwrap().is_empty());
+        assert!(checker.can_emit());
+    }
+
+    #[test]
+    fn test_broken_parent_hash_pauses_emission() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("continuity.json");
+        let (mut checker, alerts) = checker_with_alerts(&path);
+        let produced = ProducedSlots(vec![1, 2, 3, 4]);
+
+        checker.observe(&produced, 1, &block(1, 0)).unwrap();
+        checker.observe(&produced, 2, &block(2, 1)).unwrap();
+        let mut corrupted = block(3, 2);
+        corrupted.previous_blockhash = Hash::new_unique().to_string();
+        let anomaly = checker.observe(&produced, 3, &corrupted).unwrap();
+        assert!(matches!(
+            anomaly,
+            Some(ChainAnomaly::ParentHashMismatch { slot: 3, .. })
+        ));
+        assert_eq!(alerts.lock().unwrap().len(), 1);
+        assert!(!checker.can_emit());
+
+        // The next block chains onto the corrupted one, but emission stays paused
+        let mut next = block(4, 3);
+        next.previous_blockhash = corrupted.blockhash.clone();
+        assert_eq!(checker.observe(&produced, 4, &next).unwrap(), None);
+        assert!(!checker.can_emit());
+
+        // The pause survives a restart until acknowledged
+        let mut reopened = ChainContinuityChecker::open(&path).unwrap();
+        assert_eq!(reopened.unacknowledged(), anomaly.as_ref());
+        reopened.acknowledge().unwrap();
+        assert!(reopened.can_emit());
+        assert!(ChainContinuityChecker::open(&path).unwrap().can_emit());
+    }
+
+    #[test]
+    fn test_missed_blocks_and_regressions() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut checker, _) = checker_with_alerts(&dir.path().join("continuity.json"));
+        let produced = ProducedSlots(vec![1, 2, 3, 4]);
+
+        checker.observe(&produced, 1, &block(1, 0)).unwrap();
+        // Slot 2 and 3 had blocks but were not scanned
+        assert_eq!(
+            checker.observe(&produced, 4, &block(4, 3)).unwrap(),
+            Some(ChainAnomaly::ParentSlotMismatch {
+                slot: 4,
+                expected_parent_slot: 1,
+                parent_slot: 3,
+            })
+        );
+        assert_eq!(
+            checker.observe(&produced, 4, &block(4, 3)).unwrap(),
+            Some(ChainAnomaly::SlotRegression {
+                slot: 4,
+                last_slot: 4,
+            })
+        );
+    }
+
+    const EVM_ADDRESS: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+    fn deposit(evm_address: &str, nonce: Option<u64>) -> DepositEvent {
+        DepositEvent {
+            signature: Signature::new_unique(),
+            slot: 1,
+            from: Pubkey::new_unique(),
+            to: Pubkey::new_unique(),
+            lamports: 1_000,
+            evm_address: evm_address.to_string(),
+            nonce,
+            duplicate_of: None,
+        }
+    }
+
+    #[test]
+    fn test_duplicate_nonce_flagged_across_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = NonceRegistry::open(dir.path(), NonceRegistryConfig::default()).unwrap();
+
+        let first = registry.check(deposit(EVM_ADDRESS, Some(7))).unwrap();
+        assert_eq!(first.duplicate_of, None);
+        // A rescan of the same deposit is not a duplicate
+        assert_eq!(registry.check(first.clone()).unwrap().duplicate_of, None);
+        // Same nonce for another address is fine
+        let other_address = "0x0000000000000000000000000000000000000001";
+        assert_eq!(
+            registry
+                .check(deposit(other_address, Some(7)))
+                .unwrap()
+                .duplicate_of,
+            None
+        );
+        drop(registry);
+
+        let mut registry = NonceRegistry::open(dir.path(), NonceRegistryConfig::default()).unwrap();
+        // Address comparison ignores checksum casing
+        let replay = registry
+            .check(deposit(&EVM_ADDRESS.to_lowercase(), Some(7)))
+            .unwrap();
+        assert_eq!(replay.duplicate_of, Some(first.signature));
+        assert_eq!(
+            registry
+                .check(deposit(EVM_ADDRESS, Some(8)))
+                .unwrap()
+                .duplicate_of,
+            None
+        );
+    }
+
+    #[test]
+    fn test_legacy_memos_bypass_registry() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = NonceRegistry::open(dir.path(), NonceRegistryConfig::default()).unwrap();
+        for _ in 0..2 {
+            let legacy = registry.check(deposit(EVM_ADDRESS, None)).unwrap();
+            assert_eq!(legacy.duplicate_of, None);
+        }
+        // Nothing was recorded, so no file was written
+        assert!(!dir.path().join(NONCE_REGISTRY_FILE).exists());
+    }
+
+    #[test]
+    fn test_compaction_keeps_exact_nonces() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = NonceRegistryConfig { recent_window: 2 };
+        let mut registry = NonceRegistry::open(dir.path(), config).unwrap();
+
+        let mut signatures = HashMap::new();
+        for nonce in [1, 2, 5, 7, 8] {
+            let recorded = registry.check(deposit(EVM_ADDRESS, Some(nonce))).unwrap();
+            signatures.insert(nonce, recorded.signature);
+        }
+        let nonces = &registry.addresses[&EVM_ADDRESS.to_lowercase()];
+        assert_eq!(
+            nonces.recent.keys().copied().collect::<Vec<_>>(),
+            vec![7, 8]
+        );
+        assert_eq!(nonces.compacted, BTreeMap::from([(1, 2), (5, 5)]));
+        drop(registry);
+
+        // Compacted nonces are flagged with the exact deposit that used them,
+        // across a restart
+        let mut registry = NonceRegistry::open(dir.path(), config).unwrap();
+        for nonce in [1, 2, 5, 8] {
+            let replay = registry.check(deposit(EVM_ADDRESS, Some(nonce))).unwrap();
+            assert_eq!(replay.duplicate_of, Some(signatures[&nonce]));
+        }
+        // Rescanning a compacted deposit is not a duplicate
+        let mut rescan = deposit(EVM_ADDRESS, Some(2));
+        rescan.signature = signatures[&2];
+        assert_eq!(registry.check(rescan).unwrap().duplicate_of, None);
+
+        // Gaps below the compacted nonces and out-of-order nonces are accepted
+        for nonce in [3, 4, 6] {
+            let late = registry.check(deposit(EVM_ADDRESS, Some(nonce))).unwrap();
+            assert_eq!(late.duplicate_of, None);
+            signatures.insert(nonce, late.signature);
+        }
+        // Late nonces sit below the window, so they are compacted at once and
+        // close the gaps
+        let nonces = &registry.addresses[&EVM_ADDRESS.to_lowercase()];
+        assert_eq!(nonces.compacted, BTreeMap::from([(1, 6)]));
+        assert_eq!(
+            nonces.recent.keys().copied().collect::<Vec<_>>(),
+            vec![7, 8]
+        );
+        let replay = registry.check(deposit(EVM_ADDRESS, Some(4))).unwrap();
+        assert_eq!(replay.duplicate_of, Some(signatures[&4]));
+    }
+}
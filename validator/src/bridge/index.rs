@@ -0,0 +1,416 @@
+//! In-memory index of recently observed bridge deposits
+
+use {
+    crate::bridge::{notify::DedupNotifier, util::notify_deposit},
+    solana_sdk::{pubkey::Pubkey, signature::Signature},
+    std::{
+        collections::{HashMap, VecDeque},
+        sync::Arc,
+        time::{Duration, Instant, SystemTime},
+    },
+};
+
+/// A transfer-with-EVM-memo observed on chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositEvent {
+    pub signature: Signature,
+    pub slot: u64,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub lamports: u64,
+    pub evm_address: String,
+    /// Nonce from a structured memo; `None` for legacy memos
+    pub nonce: Option<u64>,
+    /// Earlier deposit that already used the same (evm_address, nonce)
+    pub duplicate_of: Option<Signature>,
+}
+
+impl DepositEvent {
+    /// `from == to`: the transfer only carries the memo, no lamports change hands
+    pub fn is_self_transfer(&self) -> bool {
+        self.from == self.to
+    }
+}
+
+/// Bounds applied to a `DepositIndex`
+#[derive(Debug, Clone)]
+pub struct DepositIndexConfig {
+    /// Maximum number of deposits kept in the index
+    pub max_entries: usize,
+    /// Deposits older than this are evicted, if set
+    pub max_age: Option<Duration>,
+}
+
+impl Default for DepositIndexConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 100_000,
+            max_age: Some(Duration::from_secs(60 * 60)),
+        }
+    }
+}
+
+struct IndexedDeposit {
+    event: DepositEvent,
+    inserted_at: Instant,
+}
+
+/// Size-bounded index over recent deposits, queryable by signature, sender,
+/// recipient and EVM address
+pub struct DepositIndex {
+    config: DepositIndexConfig,
+    // Oldest first; secondary indexes hold signatures into `by_signature`,
+    // also oldest first, so eviction only ever pops their fronts
+    order: VecDeque<Signature>,
+    by_signature: HashMap<Signature, IndexedDeposit>,
+    by_sender: HashMap<Pubkey, VecDeque<Signature>>,
+    by_recipient: HashMap<Pubkey, VecDeque<Signature>>,
+    by_evm_address: HashMap<String, VecDeque<Signature>>,
+    notifier: Option<Arc<DedupNotifier>>,
+}
+
+impl DepositIndex {
+    pub fn new(config: DepositIndexConfig) -> Self {
+        Self {
+            config,
+            order: VecDeque::new(),
+            by_signature: HashMap::new(),
+            by_sender: HashMap::new(),
+            by_recipient: HashMap::new(),
+            by_evm_address: HashMap::new(),
+            notifier: None,
+        }
+    }
+
+    /// Notify each deposit newly added by `insert`; deposits replayed by
+    /// `rebuild` are not notified again
+    pub fn with_notifier(mut self, notifier: Arc<DedupNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Rebuild an index from the newest `depth` events of a journal replay,
+    /// given oldest first with the time each deposit was observed
+    ///
+    /// Entries keep their original age, so `max_age` applies as if the index
+    /// had been running all along.
+    pub fn rebuild<I>(journal: I, depth: usize, config: DepositIndexConfig) -> Self
+    where
+        I: IntoIterator<Item = (DepositEvent, SystemTime)>,
+    {
+        let events = journal.into_iter().collect::<Vec<_>>();
+        let mut index = Self::new(config);
+        let now = Instant::now();
+        for (event, observed_at) in events.into_iter().rev().take(depth).rev() {
+            let age = SystemTime::now()
+                .duration_since(observed_at)
+                .unwrap_or_default();
+            // Too old to represent as an `Instant` means long expired
+            if let Some(inserted_at) = now.checked_sub(age) {
+                index.insert_at(event, inserted_at);
+            }
+        }
+        index.evict_expired();
+        index
+    }
+
+    /// Add a deposit; re-inserting a known signature is a no-op
+    pub fn insert(&mut self, event: DepositEvent) {
+        let notification = self.notifier.is_some().then(|| event.clone());
+        if self.insert_at(event, Instant::now()) {
+            if let (Some(notifier), Some(event)) = (&self.notifier, notification) {
+                notify_deposit(notifier.as_ref(), &event);
+            }
+        }
+    }
+
+    /// Returns whether the deposit was new
+    fn insert_at(&mut self, event: DepositEvent, inserted_at: Instant) -> bool {
+        self.evict_expired();
+        if self.by_signature.contains_key(&event.signature) {
+            return false;
+        }
+
+        let signature = event.signature;
+        self.by_sender
+            .entry(event.from)
+            .or_default()
+            .push(signature);
+        self.by_recipient
+            .entry(event.to)
+            .or_default()
+            .push(signature);
+        self.by_evm_address
+            .entry(normalize_evm_address(&event.evm_address))
+            .or_default()
+            .push(signature);
+        self.by_signature
+            .insert(signature, IndexedDeposit { event, inserted_at });
+        self.order.push_back(signature);
+
+        while self.order.len() > self.config.max_entries {
+            self.evict_oldest();
+        }
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn get(&self, signature: &Signature) -> Option<&DepositEvent> {
+        self.by_signature.get(signature).map(|d| &d.event)
+    }
+
+    /// Deposits carrying `evm_address` in their memo, oldest first.
+    /// The comparison ignores case and a leading `0x`.
+    pub fn by_evm_address(&self, evm_address: &str) -> Vec<&DepositEvent> {
+        self.lookup(self.by_evm_address.get(&normalize_evm_address(evm_address)))
+    }
+
+    /// Deposits sent from `sender`, oldest first
+    pub fn by_sender(&self, sender: &Pubkey) -> Vec<&DepositEvent> {
+        self.lookup(self.by_sender.get(sender))
+    }
+
+    /// Deposits sent to `recipient`, oldest first
+    pub fn by_recipient(&self, recipient: &Pubkey) -> Vec<&DepositEvent> {
+        self.lookup(self.by_recipient.get(recipient))
+    }
+
+    /// Deposits observed at or after `slot`, oldest first
+    pub fn since_slot(&self, slot: u64) -> Vec<&DepositEvent> {
+        self.order
+            .iter()
+            .filter_map(|signature| self.get(signature))
+            .filter(|event| event.slot >= slot)
+            .collect()
+    }
+
+    /// Drop deposits older than the configured `max_age`
+    pub fn evict_expired(&mut self) {
+        let Some(max_age) = self.config.max_age else {
+            return;
+        };
+        while let Some(oldest) = self.order.front() {
+            if self.by_signature[oldest].inserted_at.elapsed() < max_age {
+                break;
+            }
+            self.evict_oldest();
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        let Some(signature) = self.order.pop_front() else {
+            return;
+        };
+        let Some(IndexedDeposit { event, .. }) = self.by_signature.remove(&signature) else {
+            return;
+        };
+        remove_signature(&mut self.by_sender, &event.from, &signature);
+        remove_signature(&mut self.by_recipient, &event.to, &signature);
+        remove_signature(
+            &mut self.by_evm_address,
+            &normalize_evm_address(&event.evm_address),
+            &signature,
+        );
+    }
+
+    fn lookup(&self, signatures: Option<&VecDeque<Signature>>) -> Vec<&DepositEvent> {
+        signatures
+            .into_iter()
+            .flatten()
+            .filter_map(|signature| self.get(signature))
+            .collect()
+    }
+}
+
+fn normalize_evm_address(evm_address: &str) -> String {
+    let address = evm_address
+        .strip_prefix("0x")
+        .or_else(|| evm_address.strip_prefix("0X"))
+        .unwrap_or(evm_address);
+    address.to_ascii_lowercase()
+}
+
+/// Remove the oldest deposit's `signature` from the front of `key`'s list
+fn remove_signature<K: std::hash::Hash + Eq>(
+    map: &mut HashMap<K, VecDeque<Signature>>,
+    key: &K,
+    signature: &Signature,
+) {
+    if let Some(signatures) = map.get_mut(key) {
+        debug_assert_eq!(signatures.front(), Some(signature));
+        signatures.pop_front();
+        if signatures.is_empty() {
+            map.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::bridge::notify::ConfirmationNotifier, std::sync::Mutex};
+
+    fn deposit(slot: u64, from: Pubkey, to: Pubkey, evm_address: &str) -> DepositEvent {
+        DepositEvent {
+            signature: Signature::new_unique(),
+            slot,
+            from,
+            to,
+            lamports: 1_000,
+            evm_address: evm_address.to_string(),
+            nonce: None,
+            duplicate_of: None,
+        }
+    }
+
+    fn unbounded() -> DepositIndexConfig {
+        DepositIndexConfig {
+            max_entries: usize::MAX,
+            max_age: None,
+        }
+    }
+
+    #[test]
+    fn test_insert_and_query() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let evm = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+        let mut index = DepositIndex::new(unbounded());
+        let first = deposit(10, alice, vault, evm);
+        let second = deposit(11, bob, vault, "0x0000000000000000000000000000000000000001");
+        let third = deposit(12, alice, vault, &evm.to_lowercase());
+        index.insert(first.clone());
+        index.insert(second.clone());
+        index.insert(third.clone());
+        index.insert(first.clone());
+
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get(&second.signature), Some(&second));
+        assert_eq!(index.by_evm_address(evm), vec![&first, &third]);
+        assert_eq!(
+            index.by_evm_address(evm.trim_start_matches("0x")),
+            vec![&first, &third]
+        );
+        assert_eq!(index.by_sender(&alice), vec![&first, &third]);
+        assert_eq!(index.by_sender(&bob), vec![&second]);
+        assert_eq!(index.by_recipient(&vault).len(), 3);
+        assert_eq!(index.since_slot(11), vec![&second, &third]);
+        assert!(index.by_sender(&vault).is_empty());
+    }
+
+    #[test]
+    fn test_eviction() {
+        let alice = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+
+        let mut index = DepositIndex::new(DepositIndexConfig {
+            max_entries: 2,
+            max_age: None,
+        });
+        let events = (0..3)
+            .map(|slot| deposit(slot, alice, vault, "0xabc"))
+            .collect::<Vec<_>>();
+        for event in &events {
+            index.insert(event.clone());
+        }
+        assert_eq!(index.len(), 2);
+        assert!(index.get(&events[0].signature).is_none());
+        assert_eq!(index.by_sender(&alice), vec![&events[1], &events[2]]);
+
+        let mut index = DepositIndex::new(DepositIndexConfig {
+            max_entries: usize::MAX,
+            max_age: Some(Duration::ZERO),
+        });
+        index.insert(events[0].clone());
+        index.evict_expired();
+        assert!(index.is_empty());
+        assert!(index.by_evm_address("0xabc").is_empty());
+    }
+
+    #[test]
+    fn test_rebuild_matches_live_index() {
+        let alice = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let journal = (0..10)
+            .map(|slot| deposit(slot, alice, vault, "0xabc"))
+            .collect::<Vec<_>>();
+
+        let mut live = DepositIndex::new(unbounded());
+        for event in &journal[6..] {
+            live.insert(event.clone());
+        }
+        let rebuilt = DepositIndex::rebuild(
+            journal
+                .iter()
+                .map(|event| (event.clone(), SystemTime::now())),
+            4,
+            unbounded(),
+        );
+
+        assert_eq!(rebuilt.len(), live.len());
+        assert_eq!(rebuilt.since_slot(0), live.since_slot(0));
+        assert_eq!(
+            rebuilt.by_evm_address("0xABC"),
+            live.by_evm_address("0xabc")
+        );
+    }
+    #[test]
+    fn test_rebuild_keeps_deposit_age() {
+        let alice = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let old = deposit(1, alice, vault, "0xabc");
+        let recent = deposit(2, alice, vault, "0xabc");
+        let journal = vec![
+            (
+                old.clone(),
+                SystemTime::now() - Duration::from_secs(2 * 60 * 60),
+            ),
+            (recent.clone(), SystemTime::now() - Duration::from_secs(60)),
+        ];
+
+        let rebuilt = DepositIndex::rebuild(journal, 10, DepositIndexConfig::default());
+        assert_eq!(rebuilt.len(), 1);
+        assert!(rebuilt.get(&old.signature).is_none());
+        assert_eq!(rebuilt.by_sender(&alice), vec![&recent]);
+    }
+
+    struct RecordingNotifier(Arc<Mutex<Vec<Signature>>>);
+
+    impl ConfirmationNotifier for RecordingNotifier {
+        fn notify(
+            &self,
+            deposit: &DepositEvent,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.0.lock().unwrap().push(deposit.signature);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_insert_notifies_new_deposits_once() {
+        let alice = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let replayed = deposit(1, alice, vault, "0xabc");
+        let fresh = deposit(2, alice, vault, "0xabc");
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let notifier = Arc::new(DedupNotifier::new(RecordingNotifier(notified.clone())));
+
+        let mut index =
+            DepositIndex::rebuild(vec![(replayed.clone(), SystemTime::now())], 10, unbounded())
+                .with_notifier(notifier);
+        index.insert(replayed);
+        index.insert(fresh.clone());
+        index.insert(fresh.clone());
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(*notified.lock().unwrap(), vec![fresh.signature]);
+    }
+}
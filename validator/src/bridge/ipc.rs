@@ -1,18 +1,42 @@
 use {
-    crossbeam_channel::{Receiver, Sender},
+    crate::bridge::tick::{
+        TickCounters, TickCounts, TickLedger, TickLedgerEntry, TickLock, TickSource,
+    },
+    crossbeam_channel::{bounded, unbounded, Receiver, Sender},
     log::{debug, error, info, warn},
     serde::{Deserialize, Serialize},
     std::{
+        collections::HashMap,
         io::{Read, Write},
-        os::unix::net::{UnixListener, UnixStream},
+        net::Shutdown,
+        os::unix::{
+            io::AsRawFd,
+            net::{UnixListener, UnixStream},
+        },
         path::Path,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
         thread,
+        time::{Duration, Instant},
     },
 };
 
 /// Private tick message constant
 pub const PRIVATE_TICK_MESSAGE: &str = "private_therainisme_tick";
 
+/// Default time `IpcServer::start` keeps retrying a socket held by a live process
+pub const DEFAULT_BIND_TIMEOUT: Duration = Duration::from_secs(5);
+
+const BIND_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Threads handling requests when `with_worker_threads` is not set
+pub const DEFAULT_WORKER_THREADS: usize = 4;
+
+/// Longest a worker waits for the rest of a request once it started arriving
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// IPC message types
 #[derive(Debug, Serialize, Deserialize)]
 pub enum IpcMessage {
@@ -20,6 +44,25 @@ pub enum IpcMessage {
     Tick { message: String },
     /// Response message
     Response { success: bool, message: String },
+    /// Request the per-source tick counts
+    GetStats,
+    /// Response to `GetStats`
+    Stats { ticks: TickCounts },
+    /// Envelope for a pipelined request or its response
+    ///
+    /// The server processes tagged requests from one connection concurrently
+    /// and echoes `request_id` on the response, which may arrive out of order.
+    /// Untagged messages keep the lockstep request/response behavior.
+    Tagged {
+        request_id: u64,
+        message: Box<IpcMessage>,
+    },
+    /// Drive `count` ticks over one connection, answered by a single `Response`
+    TickBatch { message: String, count: u32 },
+    /// Request the recent ticks recorded by the tick ledger
+    GetTickLedger,
+    /// Response to `GetTickLedger`, oldest first
+    TickLedger { entries: Vec<TickLedgerEntry> },
 }
 
 /// IPC Server struct
@@ -27,6 +70,14 @@ pub struct IpcServer {
     socket_path: String,
     tick_sender: Sender<()>,
     tick_done_receiver: Receiver<()>,
+    accepted_tokens: Vec<String>,
+    bind_timeout: Duration,
+    tick_counters: Arc<TickCounters>,
+    // Serializes ticks across connections, pipelined requests and any local
+    // tick client sharing it, so each tick is paired with its own done signal
+    tick_lock: TickLock,
+    worker_threads: usize,
+    tick_ledger: Option<Arc<TickLedger>>,
     listener: Option<UnixListener>,
 }
 
@@ -41,19 +92,60 @@ impl IpcServer {
             socket_path,
             tick_sender,
             tick_done_receiver,
+            accepted_tokens: vec![PRIVATE_TICK_MESSAGE.to_string()],
+            bind_timeout: DEFAULT_BIND_TIMEOUT,
+            tick_counters: Arc::default(),
+            tick_lock: TickLock::default(),
+            worker_threads: DEFAULT_WORKER_THREADS,
+            tick_ledger: None,
             listener: None,
         }
     }
 
+    /// Replace the set of tick tokens the server accepts (defaults to `PRIVATE_TICK_MESSAGE`)
+    pub fn with_accepted_tokens(mut self, accepted_tokens: Vec<String>) -> Self {
+        self.accepted_tokens = accepted_tokens;
+        self
+    }
+
+    /// Record ticks into counters shared with the other tick entry points
+    pub fn with_tick_counters(mut self, tick_counters: Arc<TickCounters>) -> Self {
+        self.tick_counters = tick_counters;
+        self
+    }
+
+    /// Serialize ticks with a `LocalTickClient` driving the same channels
+    pub fn with_tick_lock(mut self, tick_lock: TickLock) -> Self {
+        self.tick_lock = tick_lock;
+        self
+    }
+
+    /// How long `start` waits for a socket held by a live process to be released
+    pub fn with_bind_timeout(mut self, bind_timeout: Duration) -> Self {
+        self.bind_timeout = bind_timeout;
+        self
+    }
+
+    /// Record every tick processed through this server in `tick_ledger`
+    pub fn with_tick_ledger(mut self, tick_ledger: Arc<TickLedger>) -> Self {
+        self.tick_ledger = Some(tick_ledger);
+        self
+    }
+
+    /// Handle requests on a fixed pool of `worker_threads` threads
+    ///
+    /// Defaults to `DEFAULT_WORKER_THREADS`. Idle connections are watched by
+    /// the accepting thread and hold no worker, so a pool of any size serves
+    /// any number of connections; requests wait while every worker is busy.
+    pub fn with_worker_threads(mut self, worker_threads: usize) -> Self {
+        self.worker_threads = worker_threads.max(1);
+        self
+    }
+
     /// Start the IPC server
     pub fn start(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // Remove existing socket file if it exists
-        if Path::new(&self.socket_path).exists() {
-            std::fs::remove_file(&self.socket_path)?;
-        }
-
         // Create Unix domain socket listener
-        let listener = UnixListener::bind(&self.socket_path)?;
+        let listener = self.bind()?;
         // info!("IPC server started, listening on socket: {}", self.socket_path);
 
         self.listener = Some(listener);
@@ -62,93 +154,232 @@ impl IpcServer {
         self.accept_connections()
     }
 
-    /// Accept client connections
-    fn accept_connections(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let listener = self.listener.as_ref().unwrap();
-
-        for stream in listener.incoming() {
-            match stream {
-                Ok(stream) => {
-                    let tick_sender = self.tick_sender.clone();
-                    let tick_done_receiver = self.tick_done_receiver.clone();
-                    thread::spawn(move || {
-                        if let Err(e) = Self::handle_client(stream, tick_sender, tick_done_receiver)
-                        {
-                            error!("Error handling client connection: {}", e);
-                        }
-                    });
+    /// Bind the socket, removing a stale socket file but never a live server's
+    ///
+    /// A socket file that accepts connections belongs to a running server; bind
+    /// is retried until `bind_timeout` in case that server is shutting down.
+    fn bind(&self) -> Result<UnixListener, Box<dyn std::error::Error + Send + Sync>> {
+        let deadline = Instant::now() + self.bind_timeout;
+        loop {
+            if Path::new(&self.socket_path).exists() {
+                if UnixStream::connect(&self.socket_path).is_err() {
+                    std::fs::remove_file(&self.socket_path)?;
+                    info!("Removed stale IPC socket file {}", self.socket_path);
                 }
-                Err(e) => {
-                    error!("Error accepting connection: {}", e);
+            }
+
+            match UnixListener::bind(&self.socket_path) {
+                Ok(listener) => return Ok(listener),
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                    if Instant::now() >= deadline {
+                        error!(
+                            "IPC socket {} is in use by a live process",
+                            self.socket_path
+                        );
+                        return Err(Box::new(std::io::Error::new(
+                            std::io::ErrorKind::AddrInUse,
+                            format!(
+                                "IPC socket {} is in use by a live process (waited {:?})",
+                                self.socket_path, self.bind_timeout
+                            ),
+                        )));
+                    }
+                    debug!("IPC socket {} in use, retrying bind", self.socket_path);
+                    thread::sleep(BIND_RETRY_INTERVAL);
                 }
+                Err(e) => return Err(e.into()),
             }
         }
-
-        Ok(())
     }
 
-    /// Handle individual client connection
-    fn handle_client(
-        mut stream: UnixStream,
-        tick_sender: Sender<()>,
-        tick_done_receiver: Receiver<()>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        info!("New client connection");
+    /// Accept client connections and hand their requests to the worker pool
+    ///
+    /// The calling thread polls the listener and every idle connection. A
+    /// connection with a request waiting goes to a worker, which reads and
+    /// answers that one request and hands the connection back.
+    fn accept_connections(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let listener = self.listener.as_ref().unwrap();
+        let context = self.request_context();
 
-        loop {
-            // Read message length (4 bytes)
-            let mut len_buf = [0u8; 4];
-            match stream.read_exact(&mut len_buf) {
-                Ok(_) => {}
-                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
-                    info!("Client disconnected");
-                    break;
+        // Workers hand connections back through `idle_sender` and wake the
+        // poll by writing to `wake_writer`
+        let (idle_sender, idle_receiver) = unbounded::<Connection>();
+        let (wake_reader, wake_writer) = UnixStream::pair()?;
+        wake_reader.set_nonblocking(true)?;
+        wake_writer.set_nonblocking(true)?;
+        let wake_writer = Arc::new(wake_writer);
+
+        let (request_sender, request_receiver) = bounded::<Connection>(self.worker_threads);
+        for _ in 0..self.worker_threads {
+            let request_receiver = request_receiver.clone();
+            let context = context.clone();
+            let idle_sender = idle_sender.clone();
+            let wake_writer = wake_writer.clone();
+            thread::spawn(move || {
+                let release = |connection| {
+                    if idle_sender.send(connection).is_ok() {
+                        // A full wake buffer already has a wakeup pending
+                        let _ = (&*wake_writer).write_all(&[0]);
+                    }
+                };
+                for connection in request_receiver {
+                    Self::handle_request(connection, &context, &release);
                 }
-                Err(e) => {
-                    error!("Error reading message length: {}", e);
-                    break;
+            });
+        }
+        info!(
+            "IPC server handling requests on {} worker threads",
+            self.worker_threads
+        );
+
+        let mut idle = Vec::<Connection>::new();
+        loop {
+            idle.extend(idle_receiver.try_iter());
+            let mut poll_fds = [listener.as_raw_fd(), wake_reader.as_raw_fd()]
+                .into_iter()
+                .chain(idle.iter().map(|connection| connection.stream.as_raw_fd()))
+                .map(|fd| libc::pollfd {
+                    fd,
+                    events: libc::POLLIN,
+                    revents: 0,
+                })
+                .collect::<Vec<_>>();
+            // SAFETY: `poll_fds` is a live array of `poll_fds.len()` entries
+            let ready =
+                unsafe { libc::poll(poll_fds.as_mut_ptr(), poll_fds.len() as libc::nfds_t, -1) };
+            if ready < 0 {
+                let e = std::io::Error::last_os_error();
+                if e.kind() == std::io::ErrorKind::Interrupted {
+                    continue;
                 }
+                error!("Error polling IPC connections: {}", e);
+                return Err(e.into());
             }
 
-            let msg_len = u32::from_le_bytes(len_buf) as usize;
-            if msg_len > 1024 * 1024 {
-                // Limit message size to 1MB
-                error!("Message too large: {} bytes", msg_len);
-                break;
+            if poll_fds[1].revents != 0 {
+                let mut wakeups = [0u8; 64];
+                while matches!((&wake_reader).read(&mut wakeups), Ok(read) if read > 0) {}
             }
+            // Highest index first, so `swap_remove` only moves checked entries
+            for index in (0..idle.len()).rev() {
+                if poll_fds[index + 2].revents != 0
+                    && request_sender.send(idle.swap_remove(index)).is_err()
+                {
+                    error!("IPC worker threads exited, no longer handling requests");
+                    return Ok(());
+                }
+            }
+            if poll_fds[0].revents != 0 {
+                match listener
+                    .accept()
+                    .and_then(|(stream, _)| Connection::new(stream))
+                {
+                    Ok(connection) => {
+                        info!("New client connection");
+                        idle.push(connection);
+                    }
+                    Err(e) => {
+                        error!("Error accepting connection: {}", e);
+                    }
+                }
+            }
+        }
+    }
 
-            // Read message content
-            let mut msg_buf = vec![0u8; msg_len];
-            if let Err(e) = stream.read_exact(&mut msg_buf) {
-                error!("Error reading message content: {}", e);
-                break;
+    /// State every request handler needs, usable from any thread
+    fn request_context(&self) -> RequestContext {
+        RequestContext {
+            tick_sender: self.tick_sender.clone(),
+            tick_done_receiver: self.tick_done_receiver.clone(),
+            accepted_tokens: self.accepted_tokens.clone().into(),
+            tick_counters: self.tick_counters.clone(),
+            tick_lock: self.tick_lock.clone(),
+            tick_ledger: self.tick_ledger.clone(),
+        }
+    }
+
+    /// Read one request from `connection` and answer it
+    ///
+    /// `release` hands the connection back to the poll loop once its next
+    /// request may be read: right after reading a pipelined request, so one
+    /// connection's pipelined requests run concurrently, or after answering a
+    /// lockstep one. A connection that is not released is closed.
+    fn handle_request(
+        mut connection: Connection,
+        context: &RequestContext,
+        release: &dyn Fn(Connection),
+    ) {
+        // Read message length (4 bytes)
+        let mut len_buf = [0u8; 4];
+        match connection.stream.read_exact(&mut len_buf) {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                info!("Client disconnected");
+                return;
             }
+            Err(e) => {
+                error!("Error reading message length: {}", e);
+                return;
+            }
+        }
 
-            // Deserialize message
-            let message: IpcMessage = match bincode::deserialize(&msg_buf) {
-                Ok(msg) => msg,
-                Err(e) => {
-                    error!("Error deserializing message: {}", e);
-                    let response = IpcMessage::Response {
-                        success: false,
-                        message: format!("Deserialization error: {}", e),
-                    };
-                    let _ = Self::send_response(&mut stream, response);
-                    continue;
-                }
-            };
+        let msg_len = u32::from_le_bytes(len_buf) as usize;
+        if msg_len > 1024 * 1024 {
+            // Limit message size to 1MB
+            error!("Message too large: {} bytes", msg_len);
+            return;
+        }
 
-            // Process message
-            let response = Self::process_message(message, &tick_sender, &tick_done_receiver);
+        // Read message content
+        let mut msg_buf = vec![0u8; msg_len];
+        if let Err(e) = connection.stream.read_exact(&mut msg_buf) {
+            error!("Error reading message content: {}", e);
+            return;
+        }
 
-            // Send response
-            if let Err(e) = Self::send_response(&mut stream, response) {
-                error!("Error sending response: {}", e);
-                break;
+        let writer = connection.writer.clone();
+        // Deserialize message
+        let message: IpcMessage = match bincode::deserialize(&msg_buf) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Error deserializing message: {}", e);
+                let response = IpcMessage::Response {
+                    success: false,
+                    message: format!("Deserialization error: {}", e),
+                };
+                let _ = Self::send_response(&mut writer.lock().unwrap(), response);
+                release(connection);
+                return;
+            }
+        };
+
+        if let IpcMessage::Tagged {
+            request_id,
+            message,
+        } = message
+        {
+            // Let another worker read the next request while this one runs;
+            // ticks still take the tick lock one at a time
+            release(connection);
+            let response = IpcMessage::Tagged {
+                request_id,
+                message: Box::new(context.process(*message)),
+            };
+            if let Err(e) = Self::send_response(&mut writer.lock().unwrap(), response) {
+                error!("Error sending response to request {}: {}", request_id, e);
             }
+            return;
         }
 
-        Ok(())
+        // Process message
+        let response = context.process(message);
+
+        // Send response
+        let sent = Self::send_response(&mut writer.lock().unwrap(), response);
+        match sent {
+            Ok(()) => release(connection),
+            Err(e) => error!("Error sending response: {}", e),
+        }
     }
 
     /// Process IPC message
@@ -156,45 +387,33 @@ impl IpcServer {
         message: IpcMessage,
         tick_sender: &Sender<()>,
         tick_done_receiver: &Receiver<()>,
+        accepted_tokens: &[String],
+        tick_counters: &TickCounters,
+        tick_lock: &Mutex<()>,
+        tick_ledger: Option<&TickLedger>,
     ) -> IpcMessage {
         match message {
             IpcMessage::Tick { message } => {
                 info!("Received tick message: {}", message);
 
-                // Check if it's the specific tick message
-                if message == PRIVATE_TICK_MESSAGE {
-                    info!("Received private_therainisme_tick message, triggering tick");
-
-                    // Send () to tick_sender to trigger tick
-                    match tick_sender.send(()) {
-                        Ok(_) => {
-                            info!("Successfully triggered tick");
-                            // Wait for the tick to be done
-                            match tick_done_receiver.recv() {
-                                Ok(_) => {
-                                    info!("Tick processing confirmed");
-                                    IpcMessage::Response {
-                                        success: true,
-                                        message: "Tick triggered and processed successfully"
-                                            .to_string(),
-                                    }
-                                }
-                                Err(e) => {
-                                    error!("Error waiting for tick done signal: {}", e);
-                                    IpcMessage::Response {
-                                        success: false,
-                                        message: format!("Failed to get tick confirmation: {}", e),
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            error!("Error triggering tick: {}", e);
-                            IpcMessage::Response {
-                                success: false,
-                                message: format!("Tick trigger failed: {}", e),
-                            }
-                        }
+                // Check if it's one of the accepted tick messages
+                if accepted_tokens.iter().any(|token| *token == message) {
+                    info!("Received accepted tick message, triggering tick");
+                    match Self::drive_tick(
+                        tick_sender,
+                        tick_done_receiver,
+                        tick_counters,
+                        tick_lock,
+                        tick_ledger,
+                    ) {
+                        Ok(()) => IpcMessage::Response {
+                            success: true,
+                            message: "Tick triggered and processed successfully".to_string(),
+                        },
+                        Err(message) => IpcMessage::Response {
+                            success: false,
+                            message,
+                        },
                     }
                 } else {
                     warn!("Received unknown tick message: {}", message);
@@ -204,16 +423,100 @@ impl IpcServer {
                     }
                 }
             }
-            IpcMessage::Response { .. } => {
+            IpcMessage::TickBatch { message, count } => {
+                info!("Received tick batch message: {} x{}", message, count);
+
+                if !accepted_tokens.iter().any(|token| *token == message) {
+                    warn!("Received unknown tick message: {}", message);
+                    return IpcMessage::Response {
+                        success: false,
+                        message: "Unknown tick message".to_string(),
+                    };
+                }
+                for done in 0..count {
+                    if let Err(e) = Self::drive_tick(
+                        tick_sender,
+                        tick_done_receiver,
+                        tick_counters,
+                        tick_lock,
+                        tick_ledger,
+                    ) {
+                        return IpcMessage::Response {
+                            success: false,
+                            message: format!(
+                                "Tick batch stopped after {} of {} ticks: {}",
+                                done, count, e
+                            ),
+                        };
+                    }
+                }
+                IpcMessage::Response {
+                    success: true,
+                    message: format!("{} ticks triggered and processed successfully", count),
+                }
+            }
+            IpcMessage::GetStats => IpcMessage::Stats {
+                ticks: tick_counters.snapshot(),
+            },
+            IpcMessage::GetTickLedger => match tick_ledger {
+                Some(tick_ledger) => IpcMessage::TickLedger {
+                    entries: tick_ledger.entries(),
+                },
+                None => IpcMessage::Response {
+                    success: false,
+                    message: "Tick ledger not enabled".to_string(),
+                },
+            },
+            IpcMessage::Response { .. }
+            | IpcMessage::Stats { .. }
+            | IpcMessage::TickLedger { .. } => {
                 warn!("Received unexpected response message");
                 IpcMessage::Response {
                     success: false,
                     message: "Unexpected response message".to_string(),
                 }
             }
+            IpcMessage::Tagged { request_id, .. } => {
+                warn!("Received nested tagged request {}", request_id);
+                IpcMessage::Response {
+                    success: false,
+                    message: "Nested tagged request".to_string(),
+                }
+            }
         }
     }
 
+    /// Trigger one tick and wait for PoH to report it done
+    fn drive_tick(
+        tick_sender: &Sender<()>,
+        tick_done_receiver: &Receiver<()>,
+        tick_counters: &TickCounters,
+        tick_lock: &Mutex<()>,
+        tick_ledger: Option<&TickLedger>,
+    ) -> Result<(), String> {
+        // Send () to tick_sender to trigger tick
+        let tick_guard = tick_lock.lock().unwrap();
+        tick_counters.record(TickSource::Ipc);
+        tick_sender.send(()).map_err(|e| {
+            error!("Error triggering tick: {}", e);
+            format!("Tick trigger failed: {}", e)
+        })?;
+        info!("Successfully triggered tick");
+
+        // Wait for the tick to be done
+        tick_done_receiver.recv().map_err(|e| {
+            error!("Error waiting for tick done signal: {}", e);
+            format!("Failed to get tick confirmation: {}", e)
+        })?;
+        info!("Tick processing confirmed");
+        // The ledger's slot source may be slow; don't hold up other ticks
+        drop(tick_guard);
+        if let Some(tick_ledger) = tick_ledger {
+            tick_ledger.record();
+        }
+        Ok(())
+    }
+
     /// Send response message
     fn send_response(
         stream: &mut UnixStream,
@@ -243,6 +546,50 @@ impl IpcServer {
     }
 }
 
+/// A client connection, owned by the poll loop while idle and by one worker
+/// while a request is read from it
+struct Connection {
+    stream: UnixStream,
+    // Responses to pipelined requests are written by several workers
+    writer: Arc<Mutex<UnixStream>>,
+}
+
+impl Connection {
+    fn new(stream: UnixStream) -> std::io::Result<Self> {
+        // A client stalling halfway through a request must not hold a worker
+        stream.set_read_timeout(Some(REQUEST_READ_TIMEOUT))?;
+        Ok(Self {
+            writer: Arc::new(Mutex::new(stream.try_clone()?)),
+            stream,
+        })
+    }
+}
+
+/// Everything `IpcServer::process_message` needs, cloned into each handler
+#[derive(Clone)]
+struct RequestContext {
+    tick_sender: Sender<()>,
+    tick_done_receiver: Receiver<()>,
+    accepted_tokens: Arc<[String]>,
+    tick_counters: Arc<TickCounters>,
+    tick_lock: TickLock,
+    tick_ledger: Option<Arc<TickLedger>>,
+}
+
+impl RequestContext {
+    fn process(&self, message: IpcMessage) -> IpcMessage {
+        IpcServer::process_message(
+            message,
+            &self.tick_sender,
+            &self.tick_done_receiver,
+            &self.accepted_tokens,
+            &self.tick_counters,
+            &self.tick_lock,
+            self.tick_ledger.as_deref(),
+        )
+    }
+}
+
 impl Drop for IpcServer {
     fn drop(&mut self) {
         self.stop();
@@ -262,14 +609,97 @@ impl IpcClient {
 
     /// Send tick message, sends "private_therainisme_tick" message to server
     pub fn tick(&self) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-        let mut stream = UnixStream::connect(&self.socket_path)?;
+        let (success, message) = self.tick_with_token(PRIVATE_TICK_MESSAGE)?;
+        if success {
+            debug!("Tick sent successfully: {}", message);
+        } else {
+            error!("Tick sending failed: {}", message);
+        }
+        Ok(success)
+    }
 
-        let message = IpcMessage::Tick {
+    /// Drive `count` ticks over a single connection
+    ///
+    /// The server replies once all ticks are done, or with the number that
+    /// succeeded if one of them fails.
+    pub fn tick_n(&self, count: u32) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let message = IpcMessage::TickBatch {
             message: PRIVATE_TICK_MESSAGE.to_string(),
+            count,
+        };
+
+        match self.send_message(&message)? {
+            IpcMessage::Response { success, message } => {
+                if success {
+                    debug!("Tick batch sent successfully: {}", message);
+                } else {
+                    error!("Tick batch failed: {}", message);
+                }
+                Ok(success)
+            }
+            response => Err(format!("Unexpected response to TickBatch: {:?}", response).into()),
+        }
+    }
+
+    /// Send tick message carrying an arbitrary token, returns the server's (success, message)
+    ///
+    /// Useful for debugging token mismatches against servers configured with
+    /// `IpcServer::with_accepted_tokens`.
+    pub fn tick_with_token(
+        &self,
+        token: &str,
+    ) -> Result<(bool, String), Box<dyn std::error::Error + Send + Sync>> {
+        let message = IpcMessage::Tick {
+            message: token.to_string(),
         };
 
+        match self.send_message(&message)? {
+            IpcMessage::Response { success, message } => Ok((success, message)),
+            _ => {
+                error!("Received unexpected response type");
+                Ok((false, "Unexpected response type".to_string()))
+            }
+        }
+    }
+
+    /// Fetch the per-source tick counts from the server
+    pub fn get_stats(&self) -> Result<TickCounts, Box<dyn std::error::Error + Send + Sync>> {
+        match self.send_message(&IpcMessage::GetStats)? {
+            IpcMessage::Stats { ticks } => Ok(ticks),
+            response => Err(format!("Unexpected response to GetStats: {:?}", response).into()),
+        }
+    }
+
+    /// Fetch the ticks recorded by the server's tick ledger, oldest first
+    pub fn get_tick_ledger(
+        &self,
+    ) -> Result<Vec<TickLedgerEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        match self.send_message(&IpcMessage::GetTickLedger)? {
+            IpcMessage::TickLedger { entries } => Ok(entries),
+            IpcMessage::Response { message, .. } => Err(message.into()),
+            response => Err(format!("Unexpected response to GetTickLedger: {:?}", response).into()),
+        }
+    }
+
+    /// Open a connection that can carry several requests at once
+    ///
+    /// Requests on the returned client are tagged with request ids, so a slow
+    /// tick does not hold up other queries on the same connection.
+    pub fn pipelined(
+        &self,
+    ) -> Result<PipelinedIpcClient, Box<dyn std::error::Error + Send + Sync>> {
+        PipelinedIpcClient::connect(&self.socket_path)
+    }
+
+    /// Send a single message over a fresh connection and read back the response
+    fn send_message(
+        &self,
+        message: &IpcMessage,
+    ) -> Result<IpcMessage, Box<dyn std::error::Error + Send + Sync>> {
+        let mut stream = UnixStream::connect(&self.socket_path)?;
+
         // Serialize message
-        let msg_bytes = bincode::serialize(&message)?;
+        let msg_bytes = bincode::serialize(message)?;
 
         // Send message length
         let len_bytes = (msg_bytes.len() as u32).to_le_bytes();
@@ -290,27 +720,164 @@ impl IpcClient {
 
         // Deserialize response
         let response: IpcMessage = bincode::deserialize(&response_buf)?;
+        Ok(response)
+    }
+}
 
-        match response {
+/// Response to a pipelined request that may not have arrived yet
+pub struct PendingResponse {
+    request_id: u64,
+    receiver: Receiver<IpcMessage>,
+}
+
+impl PendingResponse {
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    /// Block until the response arrives or the connection closes
+    pub fn wait(self) -> Result<IpcMessage, Box<dyn std::error::Error + Send + Sync>> {
+        self.receiver.recv().map_err(|_| {
+            format!(
+                "Connection closed before response to request {}",
+                self.request_id
+            )
+            .into()
+        })
+    }
+
+    /// Like `wait`, but give up after `timeout`
+    pub fn wait_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<IpcMessage, Box<dyn std::error::Error + Send + Sync>> {
+        self.receiver
+            .recv_timeout(timeout)
+            .map_err(|e| format!("No response to request {}: {}", self.request_id, e).into())
+    }
+}
+
+/// Single IPC connection carrying tagged requests, see `IpcClient::pipelined`
+///
+/// A reader thread routes each response to the request with the same id.
+pub struct PipelinedIpcClient {
+    writer: Mutex<UnixStream>,
+    next_request_id: AtomicU64,
+    pending: Arc<Mutex<HashMap<u64, Sender<IpcMessage>>>>,
+}
+
+impl PipelinedIpcClient {
+    fn connect(socket_path: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let stream = UnixStream::connect(socket_path)?;
+        let reader = stream.try_clone()?;
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+        let reader_pending = pending.clone();
+        thread::spawn(move || Self::read_responses(reader, reader_pending));
+        Ok(Self {
+            writer: Mutex::new(stream),
+            next_request_id: AtomicU64::new(0),
+            pending,
+        })
+    }
+
+    /// Send `message` without waiting for its response
+    pub fn submit(
+        &self,
+        message: IpcMessage,
+    ) -> Result<PendingResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = bounded(1);
+        self.pending.lock().unwrap().insert(request_id, sender);
+
+        let envelope = IpcMessage::Tagged {
+            request_id,
+            message: Box::new(message),
+        };
+        if let Err(e) = IpcServer::send_response(&mut self.writer.lock().unwrap(), envelope) {
+            self.pending.lock().unwrap().remove(&request_id);
+            return Err(e);
+        }
+        Ok(PendingResponse {
+            request_id,
+            receiver,
+        })
+    }
+
+    /// Send a tick and wait for it to be processed
+    pub fn tick(&self) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let message = IpcMessage::Tick {
+            message: PRIVATE_TICK_MESSAGE.to_string(),
+        };
+        match self.submit(message)?.wait()? {
             IpcMessage::Response { success, message } => {
-                if success {
-                    debug!("Tick sent successfully: {}", message);
-                } else {
+                if !success {
                     error!("Tick sending failed: {}", message);
                 }
                 Ok(success)
             }
-            _ => {
-                error!("Received unexpected response type");
-                Ok(false)
+            response => Err(format!("Unexpected response to Tick: {:?}", response).into()),
+        }
+    }
+
+    /// Fetch the per-source tick counts from the server
+    pub fn get_stats(&self) -> Result<TickCounts, Box<dyn std::error::Error + Send + Sync>> {
+        match self.submit(IpcMessage::GetStats)?.wait()? {
+            IpcMessage::Stats { ticks } => Ok(ticks),
+            response => Err(format!("Unexpected response to GetStats: {:?}", response).into()),
+        }
+    }
+
+    fn read_responses(
+        mut reader: UnixStream,
+        pending: Arc<Mutex<HashMap<u64, Sender<IpcMessage>>>>,
+    ) {
+        loop {
+            let mut len_buf = [0u8; 4];
+            if reader.read_exact(&mut len_buf).is_err() {
+                break;
+            }
+            let mut response_buf = vec![0u8; u32::from_le_bytes(len_buf) as usize];
+            if reader.read_exact(&mut response_buf).is_err() {
+                break;
+            }
+            match bincode::deserialize(&response_buf) {
+                Ok(IpcMessage::Tagged {
+                    request_id,
+                    message,
+                }) => match pending.lock().unwrap().remove(&request_id) {
+                    Some(sender) => {
+                        let _ = sender.send(*message);
+                    }
+                    None => warn!("Response to unknown request {}", request_id),
+                },
+                Ok(response) => warn!("Untagged response on pipelined connection: {:?}", response),
+                Err(e) => {
+                    error!("Error deserializing response: {}", e);
+                    break;
+                }
             }
         }
+        // Dropping the senders wakes every waiter with a disconnect
+        pending.lock().unwrap().clear();
+    }
+}
+
+impl Drop for PipelinedIpcClient {
+    fn drop(&mut self) {
+        // Unblocks the reader thread
+        let _ = self.writer.lock().unwrap().shutdown(Shutdown::Both);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use {super::*, crossbeam_channel::unbounded, std::time::Duration, tempfile::tempdir};
+    use {
+        super::*,
+        crate::bridge::tick::{LocalTickClient, TickDriver, TickLock},
+        crossbeam_channel::unbounded,
+        std::time::Duration,
+        tempfile::tempdir,
+    };
 
     #[test]
     fn test_ipc_tick_communication() {
@@ -356,6 +923,405 @@ mod tests {
         println!("IPC tick communication test completed");
     }
 
+    #[test]
+    fn test_tick_with_custom_token() {
+        solana_logger::setup();
+        let temp_dir = tempdir().unwrap();
+        let socket_path = temp_dir
+            .path()
+            .join("test_custom_token.sock")
+            .to_string_lossy()
+            .to_string();
+
+        let (tick_sender, tick_receiver) = unbounded::<()>();
+        let (tick_done_sender, tick_done_receiver) = unbounded::<()>();
+        tick_done_sender.send(()).unwrap(); // mock tick done
+
+        let custom_token = "custom_debug_token";
+        let mut server = IpcServer::new(socket_path.clone(), tick_sender, tick_done_receiver)
+            .with_accepted_tokens(vec![custom_token.to_string()]);
+        thread::spawn(move || {
+            if let Err(e) = server.start() {
+                eprintln!("Server error: {}", e);
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        let client = IpcClient::new(socket_path);
+
+        // The configured token triggers a tick
+        let (success, message) = client.tick_with_token(custom_token).unwrap();
+        assert!(success, "{}", message);
+        assert_eq!(message, "Tick triggered and processed successfully");
+        assert!(tick_receiver.recv_timeout(Duration::from_millis(100)).is_ok());
+
+        // The hardcoded default is no longer accepted by this server
+        let (success, message) = client.tick_with_token(PRIVATE_TICK_MESSAGE).unwrap();
+        assert!(!success);
+        assert_eq!(message, "Unknown tick message");
+        assert!(tick_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_bind_live_socket_reports_in_use() {
+        solana_logger::setup();
+        let temp_dir = tempdir().unwrap();
+        let socket_path = temp_dir
+            .path()
+            .join("test_bind.sock")
+            .to_string_lossy()
+            .to_string();
+
+        // A stale socket file left behind by a dead server is replaced
+        drop(UnixListener::bind(&socket_path).unwrap());
+        assert!(Path::new(&socket_path).exists());
+
+        let (tick_sender, tick_receiver) = unbounded::<()>();
+        let (tick_done_sender, tick_done_receiver) = unbounded::<()>();
+        tick_done_sender.send(()).unwrap(); // mock tick done
+        let mut server = IpcServer::new(socket_path.clone(), tick_sender, tick_done_receiver);
+        thread::spawn(move || {
+            if let Err(e) = server.start() {
+                eprintln!("Server error: {}", e);
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        // A second server on the same path must not take over the live socket
+        let (other_tick_sender, _) = unbounded::<()>();
+        let (_, other_tick_done_receiver) = unbounded::<()>();
+        let mut other = IpcServer::new(
+            socket_path.clone(),
+            other_tick_sender,
+            other_tick_done_receiver,
+        )
+        .with_bind_timeout(Duration::from_millis(200));
+        let err = other.start().unwrap_err();
+        assert!(err.to_string().contains("in use"), "{}", err);
+
+        // The first server still owns the socket
+        let client = IpcClient::new(socket_path);
+        assert!(client.tick().unwrap());
+        assert!(tick_receiver
+            .recv_timeout(Duration::from_millis(100))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_tick_counters_by_source() {
+        solana_logger::setup();
+        let temp_dir = tempdir().unwrap();
+        let socket_path = temp_dir
+            .path()
+            .join("test_stats.sock")
+            .to_string_lossy()
+            .to_string();
+
+        let (tick_sender, tick_receiver) = unbounded::<()>();
+        let (tick_done_sender, tick_done_receiver) = unbounded::<()>();
+        // Stand-in for PoH: acknowledge every tick, whichever source sent it
+        thread::spawn(move || {
+            while tick_receiver.recv().is_ok() {
+                if tick_done_sender.send(()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let tick_counters = Arc::new(TickCounters::default());
+        // Both entry points tick the same channels, so they share one lock
+        let tick_lock = TickLock::default();
+        let mut server = IpcServer::new(
+            socket_path.clone(),
+            tick_sender.clone(),
+            tick_done_receiver.clone(),
+        )
+        .with_tick_counters(tick_counters.clone())
+        .with_tick_lock(tick_lock.clone());
+        thread::spawn(move || {
+            if let Err(e) = server.start() {
+                eprintln!("Server error: {}", e);
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+        let local = Arc::new(
+            LocalTickClient::new(tick_sender, tick_done_receiver)
+                .with_tick_counters(tick_counters.clone())
+                .with_tick_lock(tick_lock),
+        );
+
+        // Local and IPC ticks interleave without stealing done signals
+        let local_ticks = {
+            let local = local.clone();
+            thread::spawn(move || {
+                for _ in 0..3 {
+                    local.trigger_tick().unwrap();
+                }
+            })
+        };
+        let client = IpcClient::new(socket_path);
+        for _ in 0..3 {
+            assert!(client.tick().unwrap());
+        }
+        local_ticks.join().unwrap();
+        local.trigger_tick().unwrap();
+        // Rejected tokens never reach the tick channel and are not counted
+        assert!(!client.tick_with_token("bogus").unwrap().0);
+
+        let expected = TickCounts {
+            ipc: 3,
+            local: 4,
+            ..TickCounts::default()
+        };
+        assert_eq!(client.get_stats().unwrap(), expected);
+        assert_eq!(tick_counters.snapshot().total(), 7);
+    }
+
+    #[test]
+    fn test_tick_batch_over_ipc() {
+        solana_logger::setup();
+        let temp_dir = tempdir().unwrap();
+        let socket_path = temp_dir
+            .path()
+            .join("test_tick_batch.sock")
+            .to_string_lossy()
+            .to_string();
+
+        let (tick_sender, tick_receiver) = unbounded::<()>();
+        let (tick_done_sender, tick_done_receiver) = unbounded::<()>();
+        // Stand-in for PoH that acknowledges two ticks, then goes away
+        thread::spawn(move || {
+            for _ in 0..2 {
+                tick_receiver.recv().unwrap();
+                tick_done_sender.send(()).unwrap();
+            }
+        });
+
+        let mut server = IpcServer::new(socket_path.clone(), tick_sender, tick_done_receiver);
+        thread::spawn(move || {
+            if let Err(e) = server.start() {
+                eprintln!("Server error: {}", e);
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        let client = IpcClient::new(socket_path);
+        assert!(client.tick_n(2).unwrap());
+        assert_eq!(client.get_stats().unwrap().ipc, 2);
+
+        // The next batch fails on its first tick and says how far it got
+        let response = client
+            .send_message(&IpcMessage::TickBatch {
+                message: PRIVATE_TICK_MESSAGE.to_string(),
+                count: 3,
+            })
+            .unwrap();
+        match response {
+            IpcMessage::Response { success, message } => {
+                assert!(!success);
+                assert!(
+                    message.starts_with("Tick batch stopped after 0 of 3 ticks"),
+                    "{}",
+                    message
+                );
+            }
+            response => panic!("unexpected response {:?}", response),
+        }
+        assert!(!client.tick_n(1).unwrap());
+
+        // Rejected tokens never reach the tick channel
+        let response = client
+            .send_message(&IpcMessage::TickBatch {
+                message: "bogus".to_string(),
+                count: 3,
+            })
+            .unwrap();
+        assert!(matches!(
+            response,
+            IpcMessage::Response { success: false, message } if message == "Unknown tick message"
+        ));
+    }
+
+    #[test]
+    fn test_tick_ledger_over_ipc() {
+        solana_logger::setup();
+        let temp_dir = tempdir().unwrap();
+        let socket_path = temp_dir
+            .path()
+            .join("test_tick_ledger.sock")
+            .to_string_lossy()
+            .to_string();
+
+        let (tick_sender, tick_receiver) = unbounded::<()>();
+        let (tick_done_sender, tick_done_receiver) = unbounded::<()>();
+        thread::spawn(move || {
+            while tick_receiver.recv().is_ok() {
+                if tick_done_sender.send(()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let tick_ledger = Arc::new(TickLedger::new(4));
+        let mut server = IpcServer::new(socket_path.clone(), tick_sender, tick_done_receiver)
+            .with_tick_ledger(tick_ledger);
+        thread::spawn(move || {
+            if let Err(e) = server.start() {
+                eprintln!("Server error: {}", e);
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        let client = IpcClient::new(socket_path);
+        for _ in 0..6 {
+            assert!(client.tick().unwrap());
+        }
+        // Rejected ticks never reach PoH and are not recorded
+        assert!(!client.tick_with_token("bogus").unwrap().0);
+
+        let sequences = client
+            .get_tick_ledger()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.sequence)
+            .collect::<Vec<_>>();
+        assert_eq!(sequences, vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_pipelined_requests_overtake_slow_tick() {
+        solana_logger::setup();
+        let temp_dir = tempdir().unwrap();
+        let socket_path = temp_dir
+            .path()
+            .join("test_pipelined.sock")
+            .to_string_lossy()
+            .to_string();
+
+        // Stand-in for PoH that holds each tick until released
+        let (tick_sender, tick_receiver) = unbounded::<()>();
+        let (tick_done_sender, tick_done_receiver) = unbounded::<()>();
+        let (held_sender, held_receiver) = unbounded::<()>();
+        let (release_sender, release_receiver) = unbounded::<()>();
+        thread::spawn(move || {
+            while tick_receiver.recv().is_ok() {
+                let _ = held_sender.send(());
+                if release_receiver.recv().is_err() || tick_done_sender.send(()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut server = IpcServer::new(socket_path.clone(), tick_sender, tick_done_receiver);
+        thread::spawn(move || {
+            if let Err(e) = server.start() {
+                eprintln!("Server error: {}", e);
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        let client = IpcClient::new(socket_path).pipelined().unwrap();
+        let tick = client
+            .submit(IpcMessage::Tick {
+                message: PRIVATE_TICK_MESSAGE.to_string(),
+            })
+            .unwrap();
+        held_receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+        let queries = (0..3)
+            .map(|_| client.submit(IpcMessage::GetStats).unwrap())
+            .collect::<Vec<_>>();
+        let mut request_ids = queries
+            .iter()
+            .map(PendingResponse::request_id)
+            .collect::<Vec<_>>();
+        request_ids.push(tick.request_id());
+        request_ids.sort_unstable();
+        request_ids.dedup();
+        assert_eq!(request_ids.len(), 4);
+
+        // Every query is answered while the tick is still held
+        for query in queries {
+            match query.wait_timeout(Duration::from_secs(1)).unwrap() {
+                IpcMessage::Stats { ticks } => assert_eq!(ticks.ipc, 1),
+                response => panic!("Unexpected response {:?}", response),
+            }
+        }
+        assert!(tick.wait_timeout(Duration::from_millis(50)).is_err());
+
+        release_sender.send(()).unwrap();
+        match tick.wait_timeout(Duration::from_secs(1)).unwrap() {
+            IpcMessage::Response { success, .. } => assert!(success),
+            response => panic!("Unexpected response {:?}", response),
+        }
+
+        // Blocking helpers share the same connection
+        release_sender.send(()).unwrap();
+        assert!(client.tick().unwrap());
+        assert_eq!(client.get_stats().unwrap().ipc, 2);
+    }
+
+    #[test]
+    fn test_worker_pool_handles_more_connections_than_threads() {
+        solana_logger::setup();
+        let temp_dir = tempdir().unwrap();
+        let socket_path = temp_dir
+            .path()
+            .join("test_pool.sock")
+            .to_string_lossy()
+            .to_string();
+
+        let (tick_sender, tick_receiver) = unbounded::<()>();
+        let (tick_done_sender, tick_done_receiver) = unbounded::<()>();
+        thread::spawn(move || {
+            while tick_receiver.recv().is_ok() {
+                if tick_done_sender.send(()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut server = IpcServer::new(socket_path.clone(), tick_sender, tick_done_receiver)
+            .with_worker_threads(2);
+        thread::spawn(move || {
+            if let Err(e) = server.start() {
+                eprintln!("Server error: {}", e);
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        // Idle connections hold no worker, so more open connections than
+        // threads leave the pool free for new ones
+        let open = (0..3)
+            .map(|_| IpcClient::new(socket_path.clone()).pipelined().unwrap())
+            .collect::<Vec<_>>();
+        // Every IpcClient call opens its own connection
+        let client = IpcClient::new(socket_path);
+        for _ in 0..10 {
+            assert!(client.tick().unwrap());
+        }
+        assert_eq!(client.get_stats().unwrap().ipc, 10);
+        for pipelined in &open {
+            assert!(pipelined.tick().unwrap());
+        }
+        assert_eq!(open[0].get_stats().unwrap().ipc, 13);
+    }
+
+    #[test]
+    fn test_message_variant_indices_are_stable() {
+        // Deployed clients encode these indices, so new variants go last
+        let index = |message: &IpcMessage| {
+            let bytes = bincode::serialize(message).unwrap();
+            u32::from_le_bytes(bytes[..4].try_into().unwrap())
+        };
+        assert_eq!(index(&IpcMessage::GetStats), 2);
+        let batch = IpcMessage::TickBatch {
+            message: PRIVATE_TICK_MESSAGE.to_string(),
+            count: 2,
+        };
+        assert_eq!(index(&batch), 5);
+        assert_eq!(index(&IpcMessage::GetTickLedger), 6);
+    }
+
     #[test]
     fn test_tick_ipc() {
         let client = IpcClient::new("/tmp/solana-private-validator".to_string());
@@ -0,0 +1,392 @@
+//! Per-transaction latency tracking from submission to a rooted slot
+//!
+//! Each tracked transaction collects timestamps keyed by its signature:
+//! submission, send, the time spent driving ticks, first processed and rooted.
+//! Once rooted, the timeline is split into phases and kept as a sample:
+//! - queueing: submitted -> sent
+//! - tick driving: total time spent inside tick calls while confirming
+//! - execution: sent -> processed, minus tick driving
+//! - rooting: processed -> rooted
+//!
+//! Transactions that never root are aged out and counted as unrooted.
+//!
+//! Note: nothing records into the ledger yet. The engine request handler, the
+//! `/metrics` endpoint and `engine_getLatencyReport` it is meant to back are
+//! not part of this tree; they should own a `LatencyLedger` and call the
+//! `record_*` methods once they exist.
+
+use {
+    solana_sdk::signature::Signature,
+    std::{
+        collections::{HashMap, VecDeque},
+        time::{Duration, Instant},
+    },
+};
+
+/// Bounds applied to a `LatencyLedger`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyLedgerConfig {
+    /// Maximum number of in-flight transactions tracked at once
+    pub max_in_flight: usize,
+    /// Maximum number of completed samples kept for percentiles
+    pub max_samples: usize,
+    /// In-flight transactions older than this are dropped as unrooted
+    pub max_age: Duration,
+}
+
+impl Default for LatencyLedgerConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 10_000,
+            max_samples: 10_000,
+            max_age: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// Phase breakdown of one rooted transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySample {
+    pub signature: Signature,
+    pub request_id: u64,
+    pub queueing: Duration,
+    pub tick_driving: Duration,
+    pub execution: Duration,
+    pub rooting: Duration,
+}
+
+impl LatencySample {
+    /// Submission to rooted slot
+    pub fn total(&self) -> Duration {
+        self.queueing + self.tick_driving + self.execution + self.rooting
+    }
+}
+
+/// Percentiles of one phase over the retained samples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseStats {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// Aggregated latency over the retained samples
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyReport {
+    pub samples: usize,
+    pub in_flight: usize,
+    /// Transactions dropped without ever reaching a rooted slot
+    pub unrooted: u64,
+    pub queueing: PhaseStats,
+    pub tick_driving: PhaseStats,
+    pub execution: PhaseStats,
+    pub rooting: PhaseStats,
+    pub total: PhaseStats,
+}
+
+struct InFlight {
+    request_id: u64,
+    submitted_at: Instant,
+    sent_at: Option<Instant>,
+    tick_driving: Duration,
+    processed_at: Option<Instant>,
+}
+
+/// Size-bounded ledger correlating latency events by signature
+pub struct LatencyLedger {
+    config: LatencyLedgerConfig,
+    next_request_id: u64,
+    // Oldest first, with the request id; entries whose transaction already
+    // rooted are skipped when they reach the front
+    order: VecDeque<(Signature, u64)>,
+    in_flight: HashMap<Signature, InFlight>,
+    samples: VecDeque<LatencySample>,
+    unrooted: u64,
+}
+
+impl LatencyLedger {
+    pub fn new(config: LatencyLedgerConfig) -> Self {
+        Self {
+            config,
+            next_request_id: 0,
+            order: VecDeque::new(),
+            in_flight: HashMap::new(),
+            samples: VecDeque::new(),
+            unrooted: 0,
+        }
+    }
+
+    /// Start tracking `signature` and return its request id
+    pub fn record_submitted(&mut self, signature: Signature, at: Instant) -> u64 {
+        self.expire(at);
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        if self.in_flight.contains_key(&signature) {
+            return request_id;
+        }
+        self.in_flight.insert(
+            signature,
+            InFlight {
+                request_id,
+                submitted_at: at,
+                sent_at: None,
+                tick_driving: Duration::ZERO,
+                processed_at: None,
+            },
+        );
+        self.order.push_back((signature, request_id));
+        while self.in_flight.len() > self.config.max_in_flight {
+            self.drop_oldest();
+        }
+        // Bound the skipped entries too; amortized over the pushes in between
+        if self.order.len() > self.config.max_in_flight.saturating_mul(2) {
+            let in_flight = &self.in_flight;
+            self.order.retain(|entry| is_live(in_flight, entry));
+        }
+        request_id
+    }
+
+    /// The transaction left the queue and was sent to the validator
+    pub fn record_sent(&mut self, signature: &Signature, at: Instant) {
+        if let Some(entry) = self.in_flight.get_mut(signature) {
+            entry.sent_at.get_or_insert(at);
+        }
+    }
+
+    /// Time spent in one tick call while confirming the transaction
+    pub fn record_tick(&mut self, signature: &Signature, duration: Duration) {
+        if let Some(entry) = self.in_flight.get_mut(signature) {
+            entry.tick_driving += duration;
+        }
+    }
+
+    /// The transaction was first seen at processed commitment
+    pub fn record_processed(&mut self, signature: &Signature, at: Instant) {
+        if let Some(entry) = self.in_flight.get_mut(signature) {
+            entry.processed_at.get_or_insert(at);
+        }
+    }
+
+    /// The transaction's slot was rooted; completes the sample
+    pub fn record_rooted(&mut self, signature: &Signature, at: Instant) -> Option<LatencySample> {
+        let entry = self.in_flight.remove(signature)?;
+
+        let sent_at = entry.sent_at.unwrap_or(entry.submitted_at);
+        let processed_at = entry.processed_at.unwrap_or(at);
+        let sent_to_processed = processed_at.saturating_duration_since(sent_at);
+        let tick_driving = entry.tick_driving.min(sent_to_processed);
+        let sample = LatencySample {
+            signature: *signature,
+            request_id: entry.request_id,
+            queueing: sent_at.saturating_duration_since(entry.submitted_at),
+            tick_driving,
+            execution: sent_to_processed - tick_driving,
+            rooting: at.saturating_duration_since(processed_at),
+        };
+        self.samples.push_back(sample);
+        while self.samples.len() > self.config.max_samples {
+            self.samples.pop_front();
+        }
+        Some(sample)
+    }
+
+    /// Drop in-flight transactions older than `max_age` as unrooted
+    pub fn expire(&mut self, now: Instant) {
+        while let Some(oldest) = self.order.front() {
+            let Some(entry) = self
+                .in_flight
+                .get(&oldest.0)
+                .filter(|entry| entry.request_id == oldest.1)
+            else {
+                self.order.pop_front();
+                continue;
+            };
+            if now.saturating_duration_since(entry.submitted_at) < self.config.max_age {
+                break;
+            }
+            self.drop_oldest();
+        }
+    }
+
+    /// Most recent completed samples, newest last
+    pub fn recent_samples(&self, limit: usize) -> Vec<LatencySample> {
+        let skip = self.samples.len().saturating_sub(limit);
+        self.samples.iter().skip(skip).copied().collect()
+    }
+
+    pub fn report(&self) -> LatencyReport {
+        let phase = |f: fn(&LatencySample) -> Duration| {
+            let mut durations = self.samples.iter().map(f).collect::<Vec<_>>();
+            durations.sort_unstable();
+            PhaseStats {
+                p50: percentile(&durations, 50),
+                p95: percentile(&durations, 95),
+                p99: percentile(&durations, 99),
+                max: durations.last().copied().unwrap_or_default(),
+            }
+        };
+        LatencyReport {
+            samples: self.samples.len(),
+            in_flight: self.in_flight.len(),
+            unrooted: self.unrooted,
+            queueing: phase(|s| s.queueing),
+            tick_driving: phase(|s| s.tick_driving),
+            execution: phase(|s| s.execution),
+            rooting: phase(|s| s.rooting),
+            total: phase(LatencySample::total),
+        }
+    }
+
+    fn drop_oldest(&mut self) {
+        while let Some(oldest) = self.order.pop_front() {
+            if is_live(&self.in_flight, &oldest) {
+                self.in_flight.remove(&oldest.0);
+                self.unrooted += 1;
+                return;
+            }
+        }
+    }
+}
+
+/// Whether an `order` entry still refers to an in-flight transaction
+fn is_live(
+    in_flight: &HashMap<Signature, InFlight>,
+    (signature, request_id): &(Signature, u64),
+) -> bool {
+    in_flight
+        .get(signature)
+        .is_some_and(|entry| entry.request_id == *request_id)
+}
+
+/// Nearest-rank percentile of sorted `durations`
+fn percentile(durations: &[Duration], percent: usize) -> Duration {
+    if durations.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percent * durations.len()).div_ceil(100).max(1);
+    durations[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_phase_attribution() {
+        let start = Instant::now();
+        let mut ledger = LatencyLedger::new(LatencyLedgerConfig::default());
+        let signature = Signature::new_unique();
+
+        let request_id = ledger.record_submitted(signature, start);
+        ledger.record_sent(&signature, start + ms(5));
+        ledger.record_tick(&signature, ms(20));
+        ledger.record_tick(&signature, ms(20));
+        ledger.record_processed(&signature, start + ms(55));
+        let sample = ledger.record_rooted(&signature, start + ms(155)).unwrap();
+
+        assert_eq!(
+            sample,
+            LatencySample {
+                signature,
+                request_id,
+                queueing: ms(5),
+                tick_driving: ms(40),
+                execution: ms(10),
+                rooting: ms(100),
+            }
+        );
+        assert_eq!(sample.total(), ms(155));
+        assert_eq!(ledger.report().in_flight, 0);
+        assert_eq!(ledger.recent_samples(10), vec![sample]);
+        // Rooting twice does not produce a second sample
+        assert!(ledger.record_rooted(&signature, start + ms(200)).is_none());
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let start = Instant::now();
+        let mut ledger = LatencyLedger::new(LatencyLedgerConfig::default());
+        for i in 1..=100 {
+            let signature = Signature::new_unique();
+            ledger.record_submitted(signature, start);
+            ledger.record_sent(&signature, start);
+            ledger.record_processed(&signature, start);
+            ledger.record_rooted(&signature, start + ms(i));
+        }
+
+        let report = ledger.report();
+        assert_eq!(report.samples, 100);
+        assert_eq!(
+            report.rooting,
+            PhaseStats {
+                p50: ms(50),
+                p95: ms(95),
+                p99: ms(99),
+                max: ms(100),
+            }
+        );
+        assert_eq!(report.total, report.rooting);
+        assert_eq!(report.queueing, PhaseStats::default());
+        assert_eq!(percentile(&[ms(7)], 95), ms(7));
+    }
+
+    #[test]
+    fn test_unrooted_age_out() {
+        let start = Instant::now();
+        let mut ledger = LatencyLedger::new(LatencyLedgerConfig {
+            max_in_flight: 2,
+            max_samples: 10,
+            max_age: ms(1_000),
+        });
+        let stuck = Signature::new_unique();
+        ledger.record_submitted(stuck, start);
+        ledger.record_sent(&stuck, start);
+
+        let rooted = Signature::new_unique();
+        ledger.record_submitted(rooted, start + ms(500));
+        ledger.record_rooted(&rooted, start + ms(600));
+
+        ledger.expire(start + ms(1_000));
+        let report = ledger.report();
+        assert_eq!(report.unrooted, 1);
+        assert_eq!(report.in_flight, 0);
+        assert_eq!(report.samples, 1);
+        assert!(ledger.record_rooted(&stuck, start + ms(1_100)).is_none());
+
+        // Exceeding max_in_flight drops the oldest as unrooted as well
+        for i in 0..3 {
+            ledger.record_submitted(Signature::new_unique(), start + ms(2_000 + i));
+        }
+        assert_eq!(ledger.report().unrooted, 2);
+        assert_eq!(ledger.report().in_flight, 2);
+    }
+
+    #[test]
+    fn test_rooted_entries_are_not_counted_unrooted() {
+        let start = Instant::now();
+        let mut ledger = LatencyLedger::new(LatencyLedgerConfig {
+            max_in_flight: 4,
+            max_samples: 10,
+            max_age: ms(1_000),
+        });
+        for i in 0..100 {
+            let signature = Signature::new_unique();
+            ledger.record_submitted(signature, start + ms(i));
+            ledger.record_rooted(&signature, start + ms(i));
+            assert!(ledger.order.len() <= 2 * 4 + 1);
+        }
+        let stuck = Signature::new_unique();
+        ledger.record_submitted(stuck, start + ms(100));
+
+        ledger.expire(start + ms(2_000));
+        let report = ledger.report();
+        assert_eq!(report.unrooted, 1);
+        assert_eq!(report.in_flight, 0);
+        assert!(ledger.order.is_empty());
+    }
+}
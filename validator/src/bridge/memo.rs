@@ -0,0 +1,162 @@
+//! Bridge deposit memo parsing
+//!
+//! Two memo formats are accepted:
+//! - legacy: the raw EVM address, with or without the `0x` prefix
+//! - structured: a JSON object such as
+//!   `{"evm_address":"0x…","chain_id":1,"nonce":7}`
+
+use {
+    crate::bridge::errors::MemoError,
+    serde::Deserialize,
+    solana_sdk::{pubkey, pubkey::Pubkey},
+};
+
+/// SPL Memo program, used when building deposit memos
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Legacy SPL Memo v1 program, still accepted when parsing
+pub const MEMO_V1_PROGRAM_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+
+/// Memo programs accepted when parsing unless configured otherwise
+pub const DEFAULT_MEMO_PROGRAM_IDS: [Pubkey; 2] = [MEMO_PROGRAM_ID, MEMO_V1_PROGRAM_ID];
+
+/// `true` for either SPL Memo program
+pub fn is_memo_program(program_id: &Pubkey) -> bool {
+    DEFAULT_MEMO_PROGRAM_IDS.contains(program_id)
+}
+
+/// A parsed deposit memo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositMemo {
+    /// Destination EVM address, always `0x`-prefixed
+    pub evm_address: String,
+    /// Target EVM chain id; `None` for legacy memos
+    pub chain_id: Option<u64>,
+    /// Deposit nonce; `None` for legacy memos
+    pub nonce: Option<u64>,
+}
+
+/// Options applied while parsing a memo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoParseOptions {
+    /// Reject memos whose chain id differs from this one. Legacy memos carry
+    /// no chain id and are rejected too when this is set.
+    pub expected_chain_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StructuredMemo {
+    evm_address: String,
+    chain_id: Option<u64>,
+    nonce: Option<u64>,
+}
+
+/// Parse memo instruction data into a `DepositMemo`
+pub fn parse_deposit_memo(
+    memo_data: &[u8],
+    options: &MemoParseOptions,
+) -> Result<DepositMemo, MemoError> {
+    let memo_text = std::str::from_utf8(memo_data)
+        .map_err(|_| MemoError::InvalidUtf8)?
+        .trim();
+
+    let memo = if memo_text.starts_with('{') {
+        let structured: StructuredMemo = serde_json::from_str(memo_text)
+            .map_err(|e| MemoError::InvalidStructuredMemo(e.to_string()))?;
+        DepositMemo {
+            evm_address: parse_evm_address(&structured.evm_address)
+                .ok_or(MemoError::InvalidEvmAddress(structured.evm_address))?,
+            chain_id: structured.chain_id,
+            nonce: structured.nonce,
+        }
+    } else {
+        DepositMemo {
+            evm_address: parse_evm_address(memo_text)
+                .ok_or_else(|| MemoError::InvalidEvmAddress(memo_text.to_string()))?,
+            chain_id: None,
+            nonce: None,
+        }
+    };
+
+    if let Some(expected) = options.expected_chain_id {
+        if memo.chain_id != Some(expected) {
+            return Err(MemoError::ChainIdMismatch {
+                expected,
+                actual: memo.chain_id,
+            });
+        }
+    }
+    Ok(memo)
+}
+
+/// Return the `0x`-prefixed address if `text` is 40 hex characters, optionally
+/// prefixed with `0x`
+pub fn parse_evm_address(text: &str) -> Option<String> {
+    let hex_part = text.strip_prefix("0x").unwrap_or(text);
+    (hex_part.len() == 40 && hex_part.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| format!("0x{hex_part}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVM_ADDRESS: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+    #[test]
+    fn test_parse_legacy_and_structured_memos() {
+        let options = MemoParseOptions::default();
+
+        let legacy = parse_deposit_memo(EVM_ADDRESS[2..].as_bytes(), &options).unwrap();
+        assert_eq!(legacy.evm_address, EVM_ADDRESS);
+        assert_eq!(legacy.chain_id, None);
+
+        let structured = format!(r#"{{"evm_address":"{EVM_ADDRESS}","chain_id":1,"nonce":7}}"#);
+        assert_eq!(
+            parse_deposit_memo(structured.as_bytes(), &options).unwrap(),
+            DepositMemo {
+                evm_address: EVM_ADDRESS.to_string(),
+                chain_id: Some(1),
+                nonce: Some(7),
+            }
+        );
+
+        assert!(matches!(
+            parse_deposit_memo(b"hello", &options),
+            Err(MemoError::InvalidEvmAddress(_))
+        ));
+        assert!(matches!(
+            parse_deposit_memo(br#"{"evm_address":"0x1"}"#, &options),
+            Err(MemoError::InvalidEvmAddress(_))
+        ));
+    }
+
+    #[test]
+    fn test_expected_chain_id() {
+        let options = MemoParseOptions {
+            expected_chain_id: Some(1),
+        };
+        let memo = |chain_id: u64| {
+            format!(r#"{{"evm_address":"{EVM_ADDRESS}","chain_id":{chain_id},"nonce":0}}"#)
+        };
+
+        let parsed = parse_deposit_memo(memo(1).as_bytes(), &options).unwrap();
+        assert_eq!(parsed.chain_id, Some(1));
+
+        assert!(matches!(
+            parse_deposit_memo(memo(56).as_bytes(), &options),
+            Err(MemoError::ChainIdMismatch {
+                expected: 1,
+                actual: Some(56)
+            })
+        ));
+        assert!(matches!(
+            parse_deposit_memo(EVM_ADDRESS.as_bytes(), &options),
+            Err(MemoError::ChainIdMismatch {
+                expected: 1,
+                actual: None
+            })
+        ));
+    }
+}
@@ -1,5 +1,18 @@
 pub mod bridge;
 pub mod config;
+pub mod errors;
+pub mod evm;
+pub mod export;
+pub mod finality;
 pub mod genesis;
+pub mod index;
 pub mod ipc;
-pub mod util;
\ No newline at end of file
+pub mod latency;
+pub mod memo;
+pub mod notify;
+pub mod outbox;
+pub mod selftest;
+pub mod spending;
+pub mod tick;
+pub mod util;
+pub mod watch;
\ No newline at end of file
@@ -0,0 +1,333 @@
+//! Notifications for confirmed bridge deposits
+//!
+//! The deposit scanners (the `util` block and address scans, `export`, and a
+//! `DepositIndex` built `with_notifier`) hand each deposit they confirm to a
+//! `DedupNotifier`, which forwards it to a `ConfirmationNotifier` (e.g. to
+//! POST it to an operator's webhook) the first time its signature is seen. A
+//! failing notifier is logged and skipped so it never interrupts a scan; the
+//! deposit is retried the next time a scan confirms it. `watch` only tracks
+//! account state and produces no deposits.
+
+use {
+    crate::bridge::index::DepositEvent,
+    log::debug,
+    serde_json::json,
+    solana_sdk::signature::Signature,
+    std::{
+        collections::{HashSet, VecDeque},
+        sync::Mutex,
+        time::Duration,
+    },
+};
+
+/// Told about every deposit a scanning helper confirms
+pub trait ConfirmationNotifier: Send + Sync {
+    fn notify(
+        &self,
+        deposit: &DepositEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Notifier that does nothing; what the helpers without a notifier use
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopNotifier;
+
+impl ConfirmationNotifier for NoopNotifier {
+    fn notify(
+        &self,
+        _deposit: &DepositEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+/// Signatures a `DedupNotifier::new` remembers before forgetting the oldest
+pub const DEFAULT_DEDUP_CAPACITY: usize = 100_000;
+
+#[derive(Default)]
+struct SeenSignatures {
+    set: HashSet<Signature>,
+    order: VecDeque<Signature>,
+}
+
+/// Forwards each deposit to the wrapped notifier once per signature
+///
+/// Scans overlap (a block is rescanned, an address is polled again), so this
+/// remembers the last `capacity` notified signatures and drops repeats. A
+/// deposit whose notification fails is forgotten so a later scan retries it.
+pub struct DedupNotifier {
+    inner: Box<dyn ConfirmationNotifier>,
+    capacity: usize,
+    seen: Mutex<SeenSignatures>,
+}
+
+impl DedupNotifier {
+    pub fn new(inner: impl ConfirmationNotifier + 'static) -> Self {
+        Self::with_capacity(inner, DEFAULT_DEDUP_CAPACITY)
+    }
+
+    pub fn with_capacity(inner: impl ConfirmationNotifier + 'static, capacity: usize) -> Self {
+        Self {
+            inner: Box::new(inner),
+            capacity: capacity.max(1),
+            seen: Mutex::new(SeenSignatures::default()),
+        }
+    }
+
+    /// Whether `signature` has already been notified
+    pub fn is_notified(&self, signature: &Signature) -> bool {
+        self.seen.lock().unwrap().set.contains(signature)
+    }
+}
+
+impl ConfirmationNotifier for DedupNotifier {
+    fn notify(
+        &self,
+        deposit: &DepositEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        {
+            let mut seen = self.seen.lock().unwrap();
+            if !seen.set.insert(deposit.signature) {
+                return Ok(());
+            }
+            seen.order.push_back(deposit.signature);
+            while seen.order.len() > self.capacity {
+                if let Some(oldest) = seen.order.pop_front() {
+                    seen.set.remove(&oldest);
+                }
+            }
+        }
+        let result = self.inner.notify(deposit);
+        if result.is_err() {
+            let mut seen = self.seen.lock().unwrap();
+            seen.set.remove(&deposit.signature);
+            seen.order
+                .retain(|signature| *signature != deposit.signature);
+        }
+        result
+    }
+}
+
+/// Request timeout used by `HttpNotifier::new`
+pub const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// POSTs each deposit as JSON (see `deposit_json`) to a webhook URL
+///
+/// Uses a blocking HTTP client, so it must not be called from inside an
+/// async runtime.
+pub struct HttpNotifier {
+    url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl HttpNotifier {
+    pub fn new(url: impl Into<String>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::with_timeout(url, DEFAULT_WEBHOOK_TIMEOUT)
+    }
+
+    pub fn with_timeout(
+        url: impl Into<String>,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()?;
+        Ok(Self {
+            url: url.into(),
+            client,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl ConfirmationNotifier for HttpNotifier {
+    fn notify(
+        &self,
+        deposit: &DepositEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&deposit_json(deposit))
+            .send()?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!(
+                "Webhook {} answered {} for {}",
+                self.url, status, deposit.signature
+            )
+            .into());
+        }
+        debug!("Notified {} of deposit {}", self.url, deposit.signature);
+        Ok(())
+    }
+}
+
+/// Webhook payload for a deposit: keys and signatures base58, camelCase fields
+pub fn deposit_json(deposit: &DepositEvent) -> serde_json::Value {
+    json!({
+        "signature": deposit.signature.to_string(),
+        "slot": deposit.slot,
+        "from": deposit.from.to_string(),
+        "to": deposit.to.to_string(),
+        "lamports": deposit.lamports,
+        "evmAddress": deposit.evm_address,
+        "nonce": deposit.nonce,
+        "duplicateOf": deposit.duplicate_of.map(|signature| signature.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{pubkey::Pubkey, signature::Signature},
+        std::{
+            io::{BufRead, BufReader, Read, Write},
+            net::TcpListener,
+            sync::Arc,
+            thread,
+        },
+    };
+
+    fn deposit() -> DepositEvent {
+        DepositEvent {
+            signature: Signature::from([3u8; 64]),
+            slot: 42,
+            from: Pubkey::new_unique(),
+            to: Pubkey::new_unique(),
+            lamports: 1_000,
+            evm_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+            nonce: Some(7),
+            duplicate_of: None,
+        }
+    }
+
+    /// Accept one request, answer with `status_line`, and return the body
+    fn serve_once(status_line: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/deposits", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(format!("{status_line}\r\nContent-Length: 0\r\n\r\n").as_bytes())
+                .unwrap();
+            String::from_utf8(body).unwrap()
+        });
+        (url, server)
+    }
+
+    #[derive(Default)]
+    struct CountingNotifier {
+        calls: Arc<Mutex<Vec<Signature>>>,
+        fail: bool,
+    }
+
+    impl ConfirmationNotifier for CountingNotifier {
+        fn notify(
+            &self,
+            deposit: &DepositEvent,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.calls.lock().unwrap().push(deposit.signature);
+            if self.fail {
+                return Err("webhook unavailable".into());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_dedup_notifier_forwards_each_signature_once() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let notifier = DedupNotifier::with_capacity(
+            CountingNotifier {
+                calls: calls.clone(),
+                fail: false,
+            },
+            2,
+        );
+        let first = deposit();
+        let second = DepositEvent {
+            signature: Signature::from([4u8; 64]),
+            ..deposit()
+        };
+        let third = DepositEvent {
+            signature: Signature::from([5u8; 64]),
+            ..deposit()
+        };
+
+        notifier.notify(&first).unwrap();
+        notifier.notify(&first).unwrap();
+        notifier.notify(&second).unwrap();
+        assert!(notifier.is_notified(&first));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![first.signature, second.signature]
+        );
+
+        // Past capacity the oldest signature is forgotten
+        notifier.notify(&third).unwrap();
+        assert!(!notifier.is_notified(&first));
+        notifier.notify(&first).unwrap();
+        assert_eq!(calls.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_dedup_notifier_retries_failed_notification() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let notifier = DedupNotifier::new(CountingNotifier {
+            calls: calls.clone(),
+            fail: true,
+        });
+        let deposit = deposit();
+
+        assert!(notifier.notify(&deposit).is_err());
+        assert!(!notifier.is_notified(&deposit.signature));
+        assert!(notifier.notify(&deposit).is_err());
+        assert_eq!(calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_http_notifier_posts_deposit() {
+        let deposit = deposit();
+        let (url, server) = serve_once("HTTP/1.1 200 OK");
+        HttpNotifier::new(url).unwrap().notify(&deposit).unwrap();
+
+        let body: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+        assert_eq!(body, deposit_json(&deposit));
+        assert_eq!(body["signature"], deposit.signature.to_string());
+        assert_eq!(body["evmAddress"], deposit.evm_address);
+        assert_eq!(body["nonce"], 7);
+    }
+
+    #[test]
+    fn test_http_notifier_rejects_error_status() {
+        let (url, server) = serve_once("HTTP/1.1 500 Internal Server Error");
+        let err = HttpNotifier::new(url)
+            .unwrap()
+            .notify(&deposit())
+            .unwrap_err();
+        assert!(err.to_string().contains("500"), "{}", err);
+        server.join().unwrap();
+    }
+}
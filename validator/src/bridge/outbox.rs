@@ -0,0 +1,739 @@
+//! Durable outbox for EVM-bound messages generated from Solana-side events
+//!
+//! Messages are persisted to `outbox.jsonl` in the outbox directory before any
+//! delivery is attempted, so a failed relayer call no longer loses the deposit.
+//! Every change appends the updated entry as one JSON line; the last line for
+//! an id wins, and the file is rewritten with only the live entries once it
+//! has grown well past their number.
+//! Pending messages are retried with exponential backoff through a `Deliverer`;
+//! a message that keeps failing is moved to the dead-letter state after
+//! `max_attempts` so it does not block the rest of the queue.
+
+use {
+    log::{debug, info, warn},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::{HashMap, HashSet},
+        fs::{self, File, OpenOptions},
+        io::Write,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, JoinHandle},
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+};
+
+const OUTBOX_FILE: &str = "outbox.jsonl";
+
+/// Records appended beyond the live entry count before the file is compacted
+const COMPACT_SLACK: usize = 64;
+
+/// A message to be delivered to the EVM side
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvmMessage {
+    /// Signature of the Solana transaction that produced the message; also the
+    /// outbox id, so enqueueing the same event twice is a no-op
+    pub source_signature: String,
+    pub slot: u64,
+    pub evm_address: String,
+    pub lamports: u64,
+}
+
+/// Delivery state of an outbox entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutboxStatus {
+    Pending,
+    /// Delivered; holds the receipt id returned by the EVM side
+    Delivered {
+        receipt_id: String,
+    },
+    /// Gave up after too many attempts, or moved here manually
+    DeadLetter,
+}
+
+/// A persisted message with its delivery state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub message: EvmMessage,
+    pub status: OutboxStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    /// Unix time in milliseconds before which the entry is not retried
+    pub next_attempt_at_ms: u64,
+}
+
+/// Counts of entries by status
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutboxStats {
+    pub pending: usize,
+    pub delivered: usize,
+    pub dead_letter: usize,
+}
+
+/// Sends a message to the EVM side, e.g. through the HTTP relayer
+pub trait Deliverer: Send + Sync {
+    /// Deliver `message` and return the EVM-side receipt id
+    fn deliver(
+        &self,
+        message: &EvmMessage,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Retry policy for an `Outbox`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutboxConfig {
+    /// Failed attempts after which a message is dead-lettered
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after every further failure
+    pub initial_backoff: Duration,
+    /// Upper bound for the retry delay
+    pub max_backoff: Duration,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// File-backed queue of EVM-bound messages
+pub struct Outbox {
+    path: PathBuf,
+    config: OutboxConfig,
+    state: Mutex<OutboxState>,
+}
+
+struct OutboxState {
+    /// Entries in enqueue order
+    entries: Vec<OutboxEntry>,
+    /// Position in `entries` by source signature
+    index: HashMap<String, usize>,
+    /// Source signatures claimed by a running delivery pass
+    in_flight: HashSet<String>,
+    log: File,
+    /// Records in the file, including superseded ones
+    records: usize,
+}
+
+impl OutboxState {
+    fn position(&self, source_signature: &str) -> Option<usize> {
+        self.index.get(source_signature).copied()
+    }
+}
+
+impl Outbox {
+    /// Open the outbox stored in `dir`, creating the directory if needed
+    pub fn open(
+        dir: &Path,
+        config: OutboxConfig,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(OUTBOX_FILE);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries: Vec<OutboxEntry> = Vec::new();
+        let mut index = HashMap::new();
+        let mut lines = contents.lines().peekable();
+        while let Some(line) = lines.next() {
+            let entry: OutboxEntry = match serde_json::from_str(line) {
+                Ok(entry) => entry,
+                // A crash mid-append can only tear the last line
+                Err(e) if lines.peek().is_none() => {
+                    warn!("Ignoring torn last record in {}: {}", path.display(), e);
+                    break;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            match index.get(&entry.message.source_signature) {
+                Some(&position) => entries[position] = entry,
+                None => {
+                    index.insert(entry.message.source_signature.clone(), entries.len());
+                    entries.push(entry);
+                }
+            }
+        }
+
+        let mut state = OutboxState {
+            entries,
+            index,
+            in_flight: HashSet::new(),
+            log: OpenOptions::new().create(true).append(true).open(&path)?,
+            records: 0,
+        };
+        Self::compact(&path, &mut state)?;
+        Ok(Self {
+            path,
+            config,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Persist `message` as pending; returns `false` if it was already enqueued
+    pub fn enqueue(
+        &self,
+        message: EvmMessage,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.state.lock().unwrap();
+        if state.index.contains_key(&message.source_signature) {
+            return Ok(false);
+        }
+        let position = state.entries.len();
+        let source_signature = message.source_signature.clone();
+        let entry = OutboxEntry {
+            message,
+            status: OutboxStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at_ms: 0,
+        };
+        self.persist(&mut state, position, entry)?;
+        debug!("Enqueued EVM message for {}", source_signature);
+        Ok(true)
+    }
+
+    /// All entries, in enqueue order
+    pub fn list(&self) -> Vec<OutboxEntry> {
+        self.state.lock().unwrap().entries.clone()
+    }
+
+    pub fn stats(&self) -> OutboxStats {
+        let state = self.state.lock().unwrap();
+        let mut stats = OutboxStats::default();
+        for entry in state.entries.iter() {
+            match entry.status {
+                OutboxStatus::Pending => stats.pending += 1,
+                OutboxStatus::Delivered { .. } => stats.delivered += 1,
+                OutboxStatus::DeadLetter => stats.dead_letter += 1,
+            }
+        }
+        stats
+    }
+
+    /// Move a dead-lettered or pending entry back to pending with a fresh
+    /// attempt budget; returns `false` if no such undelivered entry exists
+    pub fn retry(
+        &self,
+        source_signature: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.update(source_signature, |entry| {
+            entry.status = OutboxStatus::Pending;
+            entry.attempts = 0;
+            entry.next_attempt_at_ms = 0;
+        })
+    }
+
+    /// Manually dead-letter an undelivered entry; returns `false` if no such
+    /// undelivered entry exists
+    pub fn dead_letter(
+        &self,
+        source_signature: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.update(source_signature, |entry| {
+            entry.status = OutboxStatus::DeadLetter;
+        })
+    }
+
+    /// Attempt delivery of every pending entry whose backoff has elapsed;
+    /// returns the number of messages delivered
+    pub fn deliver_due(
+        &self,
+        deliverer: &dyn Deliverer,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        self.deliver_due_at(deliverer, now_ms())
+    }
+
+    fn deliver_due_at(
+        &self,
+        deliverer: &dyn Deliverer,
+        now_ms: u64,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        // Claim the due entries under the lock so a concurrent pass skips them
+        let due = {
+            let mut state = self.state.lock().unwrap();
+            let due = state
+                .entries
+                .iter()
+                .filter(|entry| {
+                    entry.status == OutboxStatus::Pending
+                        && entry.next_attempt_at_ms <= now_ms
+                        && !state.in_flight.contains(&entry.message.source_signature)
+                })
+                .map(|entry| entry.message.clone())
+                .collect::<Vec<_>>();
+            state
+                .in_flight
+                .extend(due.iter().map(|message| message.source_signature.clone()));
+            due
+        };
+
+        // Deliver without holding the lock so enqueue is never blocked on the relayer
+        let mut delivered = 0;
+        let mut due = due.into_iter();
+        while let Some(message) = due.next() {
+            let result = deliverer.deliver(&message);
+            let mut state = self.state.lock().unwrap();
+            state.in_flight.remove(&message.source_signature);
+            let Some(position) = state.position(&message.source_signature) else {
+                continue;
+            };
+            let mut entry = state.entries[position].clone();
+            if entry.status != OutboxStatus::Pending {
+                continue;
+            }
+            entry.attempts += 1;
+            match result {
+                Ok(receipt_id) => {
+                    debug!(
+                        "Delivered EVM message for {} with receipt {}",
+                        message.source_signature, receipt_id
+                    );
+                    entry.status = OutboxStatus::Delivered { receipt_id };
+                    entry.last_error = None;
+                    delivered += 1;
+                }
+                Err(e) if entry.attempts >= self.config.max_attempts => {
+                    warn!(
+                        "Dead-lettering EVM message for {} after {} attempts: {}",
+                        message.source_signature, entry.attempts, e
+                    );
+                    entry.status = OutboxStatus::DeadLetter;
+                    entry.last_error = Some(e.to_string());
+                }
+                Err(e) => {
+                    let backoff = self.backoff(entry.attempts);
+                    warn!(
+                        "Failed to deliver EVM message for {} (attempt {}): {}, retrying in {:?}",
+                        message.source_signature, entry.attempts, e, backoff
+                    );
+                    entry.last_error = Some(e.to_string());
+                    entry.next_attempt_at_ms = now_ms.saturating_add(backoff.as_millis() as u64);
+                }
+            }
+            if let Err(e) = self.persist(&mut state, position, entry) {
+                // Release the claims this pass will no longer attempt
+                for message in due {
+                    state.in_flight.remove(&message.source_signature);
+                }
+                return Err(e);
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Delay after the `attempts`-th failure
+    fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempts.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.config
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.config.max_backoff)
+    }
+
+    fn update(
+        &self,
+        source_signature: &str,
+        apply: impl FnOnce(&mut OutboxEntry),
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.state.lock().unwrap();
+        let Some(position) = state.position(source_signature).filter(|&position| {
+            !matches!(
+                state.entries[position].status,
+                OutboxStatus::Delivered { .. }
+            )
+        }) else {
+            return Ok(false);
+        };
+        let mut entry = state.entries[position].clone();
+        apply(&mut entry);
+        self.persist(&mut state, position, entry)?;
+        Ok(true)
+    }
+
+    /// Append `entry` to the file and only then store it at `position`, so a
+    /// failed write leaves the in-memory state unchanged; `position` past the
+    /// last entry adds a new one. Compacts the file once it has grown to more
+    /// than twice the live entries.
+    fn persist(
+        &self,
+        state: &mut OutboxState,
+        position: usize,
+        entry: OutboxEntry,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        state.log.write_all(&line)?;
+        state.records += 1;
+        if position == state.entries.len() {
+            state
+                .index
+                .insert(entry.message.source_signature.clone(), position);
+            state.entries.push(entry);
+        } else {
+            state.entries[position] = entry;
+        }
+        if state.records > state.entries.len() * 2 + COMPACT_SLACK {
+            Self::compact(&self.path, state)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the file with one record per live entry
+    fn compact(
+        path: &Path,
+        state: &mut OutboxState,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut contents = Vec::new();
+        for entry in &state.entries {
+            serde_json::to_writer(&mut contents, entry)?;
+            contents.push(b'\n');
+        }
+        // Write then rename so a crash never leaves a torn outbox file
+        let tmp_path = path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(tmp_path, path)?;
+        state.log = OpenOptions::new().append(true).open(path)?;
+        state.records = state.entries.len();
+        debug!("Compacted outbox to {} entries", state.records);
+        Ok(())
+    }
+}
+
+/// Background thread delivering due outbox messages every `poll_interval`
+pub struct OutboxWorker {
+    exit: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OutboxWorker {
+    pub fn start(
+        outbox: Arc<Outbox>,
+        deliverer: Arc<dyn Deliverer>,
+        poll_interval: Duration,
+    ) -> Self {
+        let exit = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let exit = exit.clone();
+            thread::spawn(move || {
+                info!("Outbox delivery worker started");
+                while !exit.load(Ordering::Relaxed) {
+                    if let Err(e) = outbox.deliver_due(deliverer.as_ref()) {
+                        warn!("Outbox delivery pass failed: {}", e);
+                    }
+                    thread::sleep(poll_interval);
+                }
+            })
+        };
+        Self {
+            exit,
+            thread: Some(thread),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.exit.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for OutboxWorker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::collections::VecDeque};
+
+    /// Returns scripted results in order, then succeeds
+    #[derive(Default)]
+    struct ScriptedDeliverer {
+        results: Mutex<VecDeque<Result<String, String>>>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl ScriptedDeliverer {
+        fn new(results: Vec<Result<&str, &str>>) -> Self {
+            Self {
+                results: Mutex::new(
+                    results
+                        .into_iter()
+                        .map(|r| r.map(str::to_string).map_err(str::to_string))
+                        .collect(),
+                ),
+                calls: Mutex::default(),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.lock().unwrap().len()
+        }
+    }
+
+    impl Deliverer for ScriptedDeliverer {
+        fn deliver(
+            &self,
+            message: &EvmMessage,
+        ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(message.source_signature.clone());
+            match self.results.lock().unwrap().pop_front() {
+                Some(Ok(receipt_id)) => Ok(receipt_id),
+                Some(Err(e)) => Err(e.into()),
+                None => Ok(format!("receipt-{}", message.source_signature)),
+            }
+        }
+    }
+
+    fn message(id: &str) -> EvmMessage {
+        EvmMessage {
+            source_signature: id.to_string(),
+            slot: 1,
+            evm_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+            lamports: 1_000,
+        }
+    }
+
+    fn config() -> OutboxConfig {
+        OutboxConfig {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(150),
+        }
+    }
+
+    #[test]
+    fn test_enqueue_and_deliver() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Outbox::open(dir.path(), config()).unwrap();
+        assert!(outbox.enqueue(message("a")).unwrap());
+        assert!(!outbox.enqueue(message("a")).unwrap());
+
+        let deliverer = ScriptedDeliverer::new(vec![Ok("receipt-1")]);
+        assert_eq!(outbox.deliver_due_at(&deliverer, 0).unwrap(), 1);
+        assert_eq!(
+            outbox.list()[0].status,
+            OutboxStatus::Delivered {
+                receipt_id: "receipt-1".to_string()
+            }
+        );
+        // Delivered messages are not sent again
+        assert_eq!(outbox.deliver_due_at(&deliverer, 0).unwrap(), 0);
+        assert_eq!(deliverer.calls(), 1);
+
+        // State survives reopening
+        let reopened = Outbox::open(dir.path(), config()).unwrap();
+        assert_eq!(reopened.list(), outbox.list());
+    }
+
+    #[test]
+    fn test_failed_persist_leaves_state_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(OUTBOX_FILE);
+        let outbox = Outbox::open(dir.path(), config()).unwrap();
+        outbox.enqueue(message("a")).unwrap();
+
+        // A read-only handle makes every append fail
+        let log = std::mem::replace(
+            &mut outbox.state.lock().unwrap().log,
+            File::open(&path).unwrap(),
+        );
+        assert!(outbox.enqueue(message("b")).is_err());
+        assert!(outbox.dead_letter("a").is_err());
+        assert_eq!(outbox.list().len(), 1);
+        assert_eq!(outbox.list()[0].status, OutboxStatus::Pending);
+
+        // Once appends work again the message is enqueued, not reported as a duplicate
+        outbox.state.lock().unwrap().log = log;
+        assert!(outbox.enqueue(message("b")).unwrap());
+        let reopened = Outbox::open(dir.path(), config()).unwrap();
+        assert_eq!(reopened.list(), outbox.list());
+    }
+
+    /// Blocks in the first delivery until released; later calls succeed at once
+    #[derive(Default)]
+    struct GatedDeliverer {
+        calls: Mutex<Vec<String>>,
+        entered: Mutex<Option<std::sync::mpsc::Sender<()>>>,
+        release: Mutex<Option<std::sync::mpsc::Receiver<()>>>,
+    }
+
+    impl Deliverer for GatedDeliverer {
+        fn deliver(
+            &self,
+            message: &EvmMessage,
+        ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(message.source_signature.clone());
+            if let Some(entered) = self.entered.lock().unwrap().take() {
+                entered.send(()).unwrap();
+                self.release.lock().unwrap().take().unwrap().recv().unwrap();
+            }
+            Ok(format!("receipt-{}", message.source_signature))
+        }
+    }
+
+    #[test]
+    fn test_concurrent_passes_do_not_double_deliver() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Outbox::open(dir.path(), config()).unwrap();
+        outbox.enqueue(message("a")).unwrap();
+        let (entered_sender, entered) = std::sync::mpsc::channel();
+        let (release, release_receiver) = std::sync::mpsc::channel();
+        let deliverer = GatedDeliverer {
+            entered: Mutex::new(Some(entered_sender)),
+            release: Mutex::new(Some(release_receiver)),
+            ..GatedDeliverer::default()
+        };
+
+        thread::scope(|scope| {
+            let first = scope.spawn(|| outbox.deliver_due_at(&deliverer, 0).unwrap());
+            entered.recv().unwrap();
+            // "a" is in flight in the first pass, so this pass has nothing to do
+            assert_eq!(outbox.deliver_due_at(&deliverer, 0).unwrap(), 0);
+            release.send(()).unwrap();
+            assert_eq!(first.join().unwrap(), 1);
+        });
+        assert_eq!(*deliverer.calls.lock().unwrap(), vec!["a".to_string()]);
+        assert_eq!(outbox.stats().delivered, 1);
+        assert!(outbox.state.lock().unwrap().in_flight.is_empty());
+    }
+
+    #[test]
+    fn test_transient_failure_backs_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Outbox::open(dir.path(), config()).unwrap();
+        outbox.enqueue(message("a")).unwrap();
+        let deliverer = ScriptedDeliverer::new(vec![Err("relayer unavailable")]);
+
+        assert_eq!(outbox.deliver_due_at(&deliverer, 1_000).unwrap(), 0);
+        let entry = &outbox.list()[0];
+        assert_eq!(entry.status, OutboxStatus::Pending);
+        assert_eq!(entry.attempts, 1);
+        assert_eq!(entry.last_error.as_deref(), Some("relayer unavailable"));
+        assert_eq!(entry.next_attempt_at_ms, 1_100);
+
+        // Not retried before the backoff elapses
+        assert_eq!(outbox.deliver_due_at(&deliverer, 1_099).unwrap(), 0);
+        assert_eq!(deliverer.calls(), 1);
+
+        assert_eq!(outbox.deliver_due_at(&deliverer, 1_100).unwrap(), 1);
+        assert_eq!(outbox.stats().delivered, 1);
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Outbox::open(dir.path(), config()).unwrap();
+        assert_eq!(outbox.backoff(1), Duration::from_millis(100));
+        assert_eq!(outbox.backoff(2), Duration::from_millis(150));
+        assert_eq!(outbox.backoff(40), Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_dead_letter_after_max_attempts() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Outbox::open(dir.path(), config()).unwrap();
+        outbox.enqueue(message("poison")).unwrap();
+        outbox.enqueue(message("ok")).unwrap();
+        let deliverer = ScriptedDeliverer::new(vec![
+            Err("bad payload"),
+            Ok("receipt-ok"),
+            Err("bad payload"),
+            Err("bad payload"),
+        ]);
+
+        let mut now = 0;
+        for _ in 0..3 {
+            outbox.deliver_due_at(&deliverer, now).unwrap();
+            now += 1_000;
+        }
+        assert_eq!(
+            outbox.stats(),
+            OutboxStats {
+                pending: 0,
+                delivered: 1,
+                dead_letter: 1,
+            }
+        );
+        assert_eq!(deliverer.calls(), 4);
+
+        // Dead-lettered messages are skipped until retried manually
+        assert_eq!(outbox.deliver_due_at(&deliverer, now).unwrap(), 0);
+        assert!(outbox.retry("poison").unwrap());
+        assert!(!outbox.retry("ok").unwrap());
+        assert_eq!(outbox.deliver_due_at(&deliverer, now).unwrap(), 1);
+        assert_eq!(outbox.stats().delivered, 2);
+    }
+
+    #[test]
+    fn test_log_is_appended_and_compacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(OUTBOX_FILE);
+        let outbox = Outbox::open(dir.path(), config()).unwrap();
+        outbox.enqueue(message("a")).unwrap();
+        outbox.enqueue(message("b")).unwrap();
+        outbox.dead_letter("a").unwrap();
+        // Each change is one appended record
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        // Flipping one entry back and forth eventually compacts the file
+        for _ in 0..COMPACT_SLACK {
+            outbox.retry("a").unwrap();
+            outbox.dead_letter("a").unwrap();
+        }
+        let records = fs::read_to_string(&path).unwrap().lines().count();
+        assert!(records <= 2 * 2 + COMPACT_SLACK, "{records} records");
+
+        let reopened = Outbox::open(dir.path(), config()).unwrap();
+        assert_eq!(reopened.list(), outbox.list());
+        assert_eq!(reopened.list()[0].status, OutboxStatus::DeadLetter);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_torn_last_record_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(OUTBOX_FILE);
+        {
+            let outbox = Outbox::open(dir.path(), config()).unwrap();
+            outbox.enqueue(message("a")).unwrap();
+            outbox.enqueue(message("b")).unwrap();
+        }
+        let mut contents = fs::read_to_string(&path).unwrap();
+        contents.truncate(contents.len() - 10);
+        fs::write(&path, contents).unwrap();
+
+        let outbox = Outbox::open(dir.path(), config()).unwrap();
+        assert_eq!(outbox.list().len(), 1);
+        assert_eq!(outbox.list()[0].message, message("a"));
+        // The torn message can be enqueued again
+        assert!(outbox.enqueue(message("b")).unwrap());
+    }
+}
@@ -0,0 +1,386 @@
+//! End-to-end self-test of the bridge loop against a freshly started validator
+//!
+//! Each check exercises one link a new environment tends to get wrong: RPC
+//! readiness and the JWT secret, the tick socket, funding an account, sending
+//! a transfer with an EVM memo, scanning it back out of its block, and the
+//! privileged reward RPC. Checks run in order and can be skipped one by one;
+//! a check whose prerequisite did not pass is reported as skipped.
+
+use {
+    crate::bridge::{
+        export::fetch_block,
+        genesis,
+        ipc::IpcClient,
+        spending::{SpendingGuard, SpendingLimits},
+        tick::TickDriver,
+        util::{
+            create_transfer_with_evm_memo, distribute_reward_to_account,
+            parse_transfer_transaction_with_context, send_and_confirm_transaction_with_driver,
+            RewardOptions, SendConfig,
+        },
+    },
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signature, Signer},
+        system_instruction,
+        transaction::Transaction,
+    },
+    std::{
+        fmt,
+        time::{Duration, Instant},
+    },
+};
+
+/// EVM address carried by the self-test deposit
+const SELFTEST_EVM_ADDRESS: &str = "0x000000000000000000000000000000000000dEaD";
+
+/// Which checks to run and how much to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelftestOptions {
+    pub skip_readiness: bool,
+    pub skip_airdrop: bool,
+    pub skip_deposit: bool,
+    pub skip_scan: bool,
+    /// Skip on chains without the privileged reward RPC
+    pub skip_reward: bool,
+    /// Lamports moved from the faucet to the throwaway account
+    pub airdrop_lamports: u64,
+    /// Lamports deposited from the throwaway account
+    pub deposit_lamports: u64,
+}
+
+impl Default for SelftestOptions {
+    fn default() -> Self {
+        Self {
+            skip_readiness: false,
+            skip_airdrop: false,
+            skip_deposit: false,
+            skip_scan: false,
+            skip_reward: false,
+            airdrop_lamports: 1_000_000_000,
+            deposit_lamports: 1_000_000,
+        }
+    }
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+/// One row of the self-test report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub elapsed: Duration,
+}
+
+/// Results of every check, in the order they ran
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelftestReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl SelftestReport {
+    /// `true` if no check failed; skipped checks do not count as failures
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| !matches!(check.status, CheckStatus::Failed(_)))
+    }
+
+    fn record<T>(
+        &mut self,
+        name: &'static str,
+        skip: Option<String>,
+        check: impl FnOnce() -> Result<T, Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Option<T> {
+        if let Some(reason) = skip {
+            self.checks.push(CheckResult {
+                name,
+                status: CheckStatus::Skipped(reason),
+                elapsed: Duration::ZERO,
+            });
+            return None;
+        }
+        let start = Instant::now();
+        let (status, value) = match check() {
+            Ok(value) => (CheckStatus::Passed, Some(value)),
+            Err(e) => (CheckStatus::Failed(e.to_string()), None),
+        };
+        self.checks.push(CheckResult {
+            name,
+            status,
+            elapsed: start.elapsed(),
+        });
+        value
+    }
+}
+
+impl fmt::Display for SelftestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<10} {:<6} {:>10}  DETAIL", "CHECK", "RESULT", "TIME")?;
+        for check in &self.checks {
+            let (result, detail) = match &check.status {
+                CheckStatus::Passed => ("pass", ""),
+                CheckStatus::Failed(e) => ("FAIL", e.as_str()),
+                CheckStatus::Skipped(reason) => ("skip", reason.as_str()),
+            };
+            writeln!(
+                f,
+                "{:<10} {:<6} {:>8}ms  {}",
+                check.name,
+                result,
+                check.elapsed.as_millis(),
+                detail
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a check is skipped: its own flag, or a prerequisite that did not pass
+fn skip_reason(skip_flag: bool, prerequisite: Option<(&str, bool)>) -> Option<String> {
+    if skip_flag {
+        return Some("skipped by flag".to_string());
+    }
+    match prerequisite {
+        Some((name, false)) => Some(format!("requires {name}")),
+        _ => None,
+    }
+}
+
+/// Run every enabled check against the validator behind `rpc_client`
+///
+/// `rpc_client` must carry the JWT secret used by the validator.
+pub fn run_selftest(
+    rpc_client: &RpcClient,
+    ipc_client: &IpcClient,
+    options: &SelftestOptions,
+) -> SelftestReport {
+    let mut report = SelftestReport::default();
+    let account = Keypair::new();
+    let recipient = Pubkey::new_unique();
+
+    report.record(
+        "readiness",
+        skip_reason(options.skip_readiness, None),
+        || check_readiness(rpc_client, ipc_client),
+    );
+
+    let funded = report
+        .record("airdrop", skip_reason(options.skip_airdrop, None), || {
+            fund_account(rpc_client, ipc_client, &account, options.airdrop_lamports)
+        })
+        .is_some();
+
+    let deposit = report.record(
+        "deposit",
+        skip_reason(options.skip_deposit, Some(("airdrop", funded))),
+        || {
+            let recent_blockhash = rpc_client.get_latest_blockhash()?;
+            let transaction = create_transfer_with_evm_memo(
+                &account,
+                &recipient,
+                options.deposit_lamports,
+                SELFTEST_EVM_ADDRESS,
+                recent_blockhash,
+            )?;
+            Ok(send_and_confirm_transaction_with_driver(
+                ipc_client,
+                rpc_client,
+                &transaction,
+                &SendConfig::default(),
+            )?)
+        },
+    );
+
+    report.record(
+        "scan",
+        skip_reason(options.skip_scan, Some(("deposit", deposit.is_some()))),
+        || {
+            let signature = deposit.expect("scan runs only after a deposit");
+            scan_deposit(
+                rpc_client,
+                &signature,
+                &account.pubkey(),
+                &recipient,
+                options.deposit_lamports,
+            )
+        },
+    );
+
+    report.record("reward", skip_reason(options.skip_reward, None), || {
+        check_reward(rpc_client, ipc_client, &recipient)
+    });
+
+    report
+}
+
+fn check_readiness(
+    rpc_client: &RpcClient,
+    tick_driver: &dyn TickDriver,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    rpc_client.get_health()?;
+    if rpc_client.get_auth_token_secret().is_none() {
+        return Err("JWT secret not set on the RPC client".into());
+    }
+    let slot = rpc_client.get_slot()?;
+    tick_driver
+        .trigger_tick()
+        .map_err(|e| format!("Tick failed at slot {slot}: {e}"))?;
+    Ok(())
+}
+
+fn fund_account(
+    rpc_client: &RpcClient,
+    tick_driver: &dyn TickDriver,
+    account: &Keypair,
+    lamports: u64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let faucet = genesis::faucet_keypair();
+    let recent_blockhash = rpc_client.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &faucet.pubkey(),
+            &account.pubkey(),
+            lamports,
+        )],
+        Some(&faucet.pubkey()),
+        &[&faucet],
+        recent_blockhash,
+    );
+    send_and_confirm_transaction_with_driver(
+        tick_driver,
+        rpc_client,
+        &transaction,
+        &SendConfig::default(),
+    )?;
+    let balance = rpc_client.get_balance(&account.pubkey())?;
+    if balance != lamports {
+        return Err(format!("Funded account holds {balance} lamports, expected {lamports}").into());
+    }
+    Ok(())
+}
+
+fn scan_deposit(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+    from: &Pubkey,
+    to: &Pubkey,
+    lamports: u64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let slot = rpc_client
+        .get_signature_statuses(&[*signature])?
+        .value
+        .into_iter()
+        .flatten()
+        .next()
+        .ok_or_else(|| format!("No status for deposit {signature}"))?
+        .slot;
+    let block = fetch_block(rpc_client, slot)?
+        .ok_or_else(|| format!("Block at slot {slot} not available"))?;
+
+    let transaction = block
+        .transactions
+        .iter()
+        .flatten()
+        .filter_map(|tx| tx.transaction.decode()?.into_legacy_transaction())
+        .find(|tx| tx.signatures.first() == Some(signature))
+        .ok_or_else(|| format!("Deposit {signature} not found in block at slot {slot}"))?;
+    let deposit = parse_transfer_transaction_with_context(&transaction, slot)?
+        .ok_or_else(|| format!("Deposit {signature} did not parse as a transfer"))?;
+
+    let expected = (*from, *to, lamports, SELFTEST_EVM_ADDRESS.to_string());
+    let parsed = (
+        deposit.from,
+        deposit.to,
+        deposit.lamports,
+        deposit.evm_address.clone(),
+    );
+    if parsed != expected {
+        return Err(format!("Scanned deposit {parsed:?} differs from sent {expected:?}").into());
+    }
+    Ok(())
+}
+
+fn check_reward(
+    rpc_client: &RpcClient,
+    ipc_client: &IpcClient,
+    recipient: &Pubkey,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let before = rpc_client.get_balance(recipient)?;
+    // The check only ever moves a single lamport
+    let guard = SpendingGuard::in_memory(SpendingLimits {
+        per_call_cap: Some(1),
+        ..SpendingLimits::default()
+    });
+    distribute_reward_to_account(
+        rpc_client,
+        ipc_client,
+        recipient,
+        1,
+        &RewardOptions::guarded(&guard),
+    )?;
+    let after = rpc_client.get_balance(recipient)?;
+    if after != before + 1 {
+        return Err(format!("Reward of 1 lamport moved balance from {before} to {after}").into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_skips_and_failures() {
+        let mut report = SelftestReport::default();
+        assert_eq!(
+            report.record::<()>("airdrop", None, || Err("faucet empty".into())),
+            None
+        );
+        let deposit = report.record(
+            "deposit",
+            skip_reason(false, Some(("airdrop", false))),
+            || Ok(1),
+        );
+        assert_eq!(deposit, None);
+        assert_eq!(
+            report.record("reward", skip_reason(true, None), || Ok(())),
+            None
+        );
+        assert_eq!(report.record("readiness", None, || Ok(7)), Some(7));
+
+        let statuses = report
+            .checks
+            .iter()
+            .map(|check| (check.name, check.status.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                ("airdrop", CheckStatus::Failed("faucet empty".to_string())),
+                (
+                    "deposit",
+                    CheckStatus::Skipped("requires airdrop".to_string())
+                ),
+                (
+                    "reward",
+                    CheckStatus::Skipped("skipped by flag".to_string())
+                ),
+                ("readiness", CheckStatus::Passed),
+            ]
+        );
+        assert!(!report.passed());
+
+        let table = report.to_string();
+        assert!(table.contains("FAIL"), "{}", table);
+        assert!(table.contains("faucet empty"), "{}", table);
+    }
+}
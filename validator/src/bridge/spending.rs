@@ -0,0 +1,402 @@
+//! Client-side spending limits for reward distribution
+//!
+//! A `SpendingGuard` is consulted before any reward RPC is made. It enforces a
+//! per-call cap, a cap over a rolling time window and an optional recipient
+//! allow-list. Authorized spends are recorded in a JSON file before the RPC is
+//! sent, so a restart (or a crash halfway through a distribution) does not
+//! reset the window. Limits can only be bypassed with an explicit `force`.
+//!
+//! Every `distribute_reward*` helper in `util` takes a guard, so there is no
+//! unguarded way to distribute rewards through this crate.
+
+use {
+    crate::bridge::errors::BridgeError,
+    log::warn,
+    serde::{Deserialize, Serialize},
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::HashSet,
+        fs,
+        path::{Path, PathBuf},
+        sync::Mutex,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Limits enforced by a `SpendingGuard`; `None` disables a limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendingLimits {
+    /// Maximum lamports for a single distribution
+    pub per_call_cap: Option<u64>,
+    /// Maximum lamports distributed within `window`
+    pub window_cap: Option<u64>,
+    /// Length of the rolling window, e.g. 24h
+    pub window: Duration,
+    /// Recipients allowed to receive rewards; any recipient when `None`
+    pub allow_list: Option<HashSet<Pubkey>>,
+}
+
+impl Default for SpendingLimits {
+    fn default() -> Self {
+        Self {
+            per_call_cap: None,
+            window_cap: None,
+            window: Duration::from_secs(24 * 60 * 60),
+            allow_list: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SpendRecord {
+    at_ms: u64,
+    lamports: u64,
+    recipient: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct SpendingState {
+    // Oldest first
+    spends: Vec<SpendRecord>,
+}
+
+/// Enforces `SpendingLimits` and persists the rolling window
+pub struct SpendingGuard {
+    // `None` keeps the history in memory only
+    path: Option<PathBuf>,
+    limits: SpendingLimits,
+    state: Mutex<SpendingState>,
+}
+
+impl SpendingGuard {
+    /// Open the spend history stored at `path`; a missing file starts empty
+    pub fn open(
+        path: &Path,
+        limits: SpendingLimits,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let state = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => SpendingState::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            limits,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Guard whose spend history is not persisted, e.g. for one-off checks
+    pub fn in_memory(limits: SpendingLimits) -> Self {
+        Self {
+            path: None,
+            limits,
+            state: Mutex::new(SpendingState::default()),
+        }
+    }
+
+    pub fn limits(&self) -> &SpendingLimits {
+        &self.limits
+    }
+
+    /// Lamports that can still be spent in the current window
+    pub fn remaining(&self) -> Option<u64> {
+        self.remaining_at(now_ms())
+    }
+
+    /// Check `lamports` to `recipient` against the limits and record the spend
+    ///
+    /// The spend is recorded before the caller makes the RPC, so a failed call
+    /// still counts against the window. `force` skips every check, but the
+    /// spend is recorded all the same.
+    pub fn authorize(
+        &self,
+        recipient: &Pubkey,
+        lamports: u64,
+        force: bool,
+    ) -> Result<(), BridgeError> {
+        self.authorize_all_at(&[(*recipient, lamports)], force, now_ms())
+    }
+
+    /// Check a whole batch of spends and record all of them, or none
+    ///
+    /// The window cap applies to the batch total, so a batch is rejected
+    /// before any of its RPCs are made.
+    pub fn authorize_all(&self, spends: &[(Pubkey, u64)], force: bool) -> Result<(), BridgeError> {
+        self.authorize_all_at(spends, force, now_ms())
+    }
+
+    #[cfg(test)]
+    fn authorize_at(
+        &self,
+        recipient: &Pubkey,
+        lamports: u64,
+        force: bool,
+        now_ms: u64,
+    ) -> Result<(), BridgeError> {
+        self.authorize_all_at(&[(*recipient, lamports)], force, now_ms)
+    }
+
+    fn authorize_all_at(
+        &self,
+        spends: &[(Pubkey, u64)],
+        force: bool,
+        now_ms: u64,
+    ) -> Result<(), BridgeError> {
+        let mut state = self.state.lock().unwrap();
+        self.prune(&mut state, now_ms);
+
+        // Only keep the spends in memory once they are on disk
+        let mut next = state.clone();
+        for (recipient, lamports) in spends {
+            if force {
+                warn!(
+                    "Spending limits overridden: {} lamports to {}",
+                    lamports, recipient
+                );
+            } else {
+                self.check(&next, recipient, *lamports)?;
+            }
+            next.spends.push(SpendRecord {
+                at_ms: now_ms,
+                lamports: *lamports,
+                recipient: recipient.to_string(),
+            });
+        }
+        self.persist(&next)?;
+        *state = next;
+        Ok(())
+    }
+
+    fn check(
+        &self,
+        state: &SpendingState,
+        recipient: &Pubkey,
+        lamports: u64,
+    ) -> Result<(), BridgeError> {
+        if let Some(allow_list) = &self.limits.allow_list {
+            if !allow_list.contains(recipient) {
+                return Err(BridgeError::RecipientNotAllowed {
+                    recipient: *recipient,
+                });
+            }
+        }
+        if let Some(per_call_cap) = self.limits.per_call_cap {
+            if lamports > per_call_cap {
+                return Err(BridgeError::PerCallLimitExceeded {
+                    requested: lamports,
+                    cap: per_call_cap,
+                });
+            }
+        }
+        if let Some(remaining) = self.window_remaining(state) {
+            if lamports > remaining {
+                return Err(BridgeError::SpendingLimitExceeded {
+                    requested: lamports,
+                    remaining,
+                    window: self.limits.window,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn remaining_at(&self, now_ms: u64) -> Option<u64> {
+        let mut state = self.state.lock().unwrap();
+        self.prune(&mut state, now_ms);
+        self.window_remaining(&state)
+    }
+
+    fn window_remaining(&self, state: &SpendingState) -> Option<u64> {
+        let spent = state
+            .spends
+            .iter()
+            .fold(0u64, |sum, spend| sum.saturating_add(spend.lamports));
+        self.limits.window_cap.map(|cap| cap.saturating_sub(spent))
+    }
+
+    /// Drop spends that fell out of the rolling window
+    fn prune(&self, state: &mut SpendingState, now_ms: u64) {
+        let window_start = now_ms.saturating_sub(self.limits.window.as_millis() as u64);
+        state.spends.retain(|spend| spend.at_ms > window_start);
+    }
+
+    fn persist(&self, state: &SpendingState) -> Result<(), BridgeError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let persistence_error = |e: &dyn std::fmt::Display| {
+            BridgeError::SpendingPersistence(format!("{}: {e}", path.display()))
+        };
+        // Write then rename so a crash never leaves a torn history file
+        let tmp_path = path.with_extension("tmp");
+        let bytes = serde_json::to_vec(state).map_err(|e| persistence_error(&e))?;
+        fs::write(&tmp_path, bytes).map_err(|e| persistence_error(&e))?;
+        fs::rename(tmp_path, path).map_err(|e| persistence_error(&e))?;
+        Ok(())
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: u64 = 60 * 60 * 1000;
+
+    fn limits() -> SpendingLimits {
+        SpendingLimits {
+            per_call_cap: Some(500),
+            window_cap: Some(1_000),
+            window: Duration::from_millis(24 * HOUR_MS),
+            allow_list: None,
+        }
+    }
+
+    fn limit_exceeded(result: Result<(), BridgeError>) -> Option<(u64, u64, Duration)> {
+        match result.unwrap_err() {
+            BridgeError::SpendingLimitExceeded {
+                requested,
+                remaining,
+                window,
+            } => Some((requested, remaining, window)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let guard = SpendingGuard::open(&dir.path().join("spending.json"), limits()).unwrap();
+        let recipient = Pubkey::new_unique();
+        let start = 1_700_000_000_000;
+
+        guard.authorize_at(&recipient, 400, false, start).unwrap();
+        guard
+            .authorize_at(&recipient, 400, false, start + HOUR_MS)
+            .unwrap();
+        assert_eq!(guard.remaining_at(start + 2 * HOUR_MS), Some(200));
+        assert_eq!(
+            limit_exceeded(guard.authorize_at(&recipient, 300, false, start + 2 * HOUR_MS)),
+            Some((300, 200, Duration::from_millis(24 * HOUR_MS)))
+        );
+        // Per-call cap applies regardless of the window
+        let err = guard
+            .authorize_at(&recipient, 600, false, start + 2 * HOUR_MS)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BridgeError::PerCallLimitExceeded {
+                requested: 600,
+                cap: 500,
+            }
+        ));
+
+        // The first spend leaves the window after 24h
+        assert_eq!(guard.remaining_at(start + 24 * HOUR_MS), Some(600));
+        guard
+            .authorize_at(&recipient, 500, false, start + 24 * HOUR_MS)
+            .unwrap();
+        assert_eq!(guard.remaining_at(start + 25 * HOUR_MS), Some(500));
+    }
+
+    #[test]
+    fn test_window_survives_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spending.json");
+        let recipient = Pubkey::new_unique();
+        let start = 1_700_000_000_000;
+
+        let guard = SpendingGuard::open(&path, limits()).unwrap();
+        guard.authorize_at(&recipient, 450, false, start).unwrap();
+        guard.authorize_at(&recipient, 450, false, start).unwrap();
+        drop(guard);
+
+        let reopened = SpendingGuard::open(&path, limits()).unwrap();
+        assert_eq!(reopened.remaining_at(start + HOUR_MS), Some(100));
+        assert!(
+            limit_exceeded(reopened.authorize_at(&recipient, 200, false, start + HOUR_MS))
+                .is_some()
+        );
+
+        // Forced spends bypass the limits but are still recorded
+        reopened
+            .authorize_at(&recipient, 200, true, start + HOUR_MS)
+            .unwrap();
+        assert_eq!(reopened.remaining_at(start + HOUR_MS), Some(0));
+    }
+
+    #[test]
+    fn test_allow_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let guard = SpendingGuard::open(
+            &dir.path().join("spending.json"),
+            SpendingLimits {
+                allow_list: Some(HashSet::from([allowed])),
+                ..limits()
+            },
+        )
+        .unwrap();
+
+        let now = 1_700_000_000_000;
+        guard.authorize_at(&allowed, 100, false, now).unwrap();
+        let err = guard.authorize_at(&other, 100, false, now).unwrap_err();
+        assert!(matches!(
+            err,
+            BridgeError::RecipientNotAllowed { recipient } if recipient == other
+        ));
+        // A rejected spend does not count against the window
+        assert_eq!(guard.remaining_at(now), Some(900));
+    }
+
+    #[test]
+    fn test_batch_is_all_or_nothing() {
+        let guard = SpendingGuard::in_memory(limits());
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let now = 1_700_000_000_000;
+
+        // Each spend is under the per-call cap but the batch exceeds the window
+        assert_eq!(
+            limit_exceeded(guard.authorize_all_at(
+                &[(first, 500), (second, 500), (first, 100)],
+                false,
+                now
+            )),
+            Some((100, 0, Duration::from_millis(24 * HOUR_MS)))
+        );
+        assert_eq!(guard.remaining_at(now), Some(1_000));
+
+        guard
+            .authorize_all_at(&[(first, 500), (second, 500)], false, now)
+            .unwrap();
+        assert_eq!(guard.remaining_at(now), Some(0));
+    }
+
+    #[test]
+    fn test_failed_persist_does_not_record_spend() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join("spending.json");
+        let guard = SpendingGuard::open(&path, limits()).unwrap();
+        let recipient = Pubkey::new_unique();
+        let now = 1_700_000_000_000;
+
+        assert!(matches!(
+            guard.authorize_at(&recipient, 400, false, now),
+            Err(BridgeError::SpendingPersistence(_))
+        ));
+        assert_eq!(guard.remaining_at(now), Some(1_000));
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        guard.authorize_at(&recipient, 400, false, now).unwrap();
+        assert_eq!(guard.remaining_at(now), Some(600));
+    }
+}
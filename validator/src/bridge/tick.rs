@@ -0,0 +1,631 @@
+//! Manual tick drivers used to advance the private validator
+
+use {
+    crate::bridge::{errors::BridgeError, ipc::IpcClient, util::ConfirmationRpc},
+    crossbeam_channel::{Receiver, Sender},
+    log::info,
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::VecDeque,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex, RwLock,
+        },
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Entry point a manual tick came through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickSource {
+    Ipc,
+    Local,
+    Engine,
+    Admin,
+}
+
+/// Number of ticks sent by each source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickCounts {
+    pub ipc: u64,
+    pub local: u64,
+    pub engine: u64,
+    pub admin: u64,
+}
+
+impl TickCounts {
+    pub fn total(&self) -> u64 {
+        self.ipc + self.local + self.engine + self.admin
+    }
+}
+
+/// Per-source tick counters shared by every tick entry point
+///
+/// Each entry point records its source just before sending on the tick
+/// channel, so the channel payload stays `()` and the PoH side is unchanged.
+#[derive(Debug, Default)]
+pub struct TickCounters {
+    ipc: AtomicU64,
+    local: AtomicU64,
+    engine: AtomicU64,
+    admin: AtomicU64,
+}
+
+impl TickCounters {
+    pub fn record(&self, source: TickSource) {
+        let counter = match source {
+            TickSource::Ipc => &self.ipc,
+            TickSource::Local => &self.local,
+            TickSource::Engine => &self.engine,
+            TickSource::Admin => &self.admin,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> TickCounts {
+        TickCounts {
+            ipc: self.ipc.load(Ordering::Relaxed),
+            local: self.local.load(Ordering::Relaxed),
+            engine: self.engine.load(Ordering::Relaxed),
+            admin: self.admin.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// One processed tick recorded by a `TickLedger`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickLedgerEntry {
+    /// Position of the tick among all ticks the ledger has seen, from 0
+    pub sequence: u64,
+    /// Unix time in milliseconds when the tick was confirmed done
+    pub timestamp_ms: u64,
+    /// Slot reported by the slot source after the tick, if one is set
+    pub resulting_slot: Option<u64>,
+}
+
+/// Reads the validator's current slot for `TickLedger` entries
+pub type SlotSource = Box<dyn Fn() -> Option<u64> + Send + Sync>;
+
+/// Ring buffer of the last ticks, for correlating tick timing with slot
+/// boundaries when debugging slot progression
+pub struct TickLedger {
+    capacity: usize,
+    next_sequence: AtomicU64,
+    entries: Mutex<VecDeque<TickLedgerEntry>>,
+    slot_source: Option<SlotSource>,
+}
+
+impl TickLedger {
+    /// Keep the last `capacity` ticks
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            next_sequence: AtomicU64::new(0),
+            entries: Mutex::new(VecDeque::with_capacity(capacity.max(1))),
+            slot_source: None,
+        }
+    }
+
+    /// Fill `resulting_slot` from `slot_source`, e.g. a bank forks lookup
+    pub fn with_slot_source(mut self, slot_source: SlotSource) -> Self {
+        self.slot_source = Some(slot_source);
+        self
+    }
+
+    /// Record a tick that has just been confirmed done
+    pub fn record(&self) {
+        let resulting_slot = self
+            .slot_source
+            .as_ref()
+            .and_then(|slot_source| slot_source());
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let mut entries = self.entries.lock().unwrap();
+        // Taken under the lock so entries stay in sequence order
+        let sequence = self.next_sequence.fetch_add(1, Ordering::Relaxed);
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(TickLedgerEntry {
+            sequence,
+            timestamp_ms,
+            resulting_slot,
+        });
+    }
+
+    /// Recorded ticks, oldest first
+    pub fn entries(&self) -> Vec<TickLedgerEntry> {
+        self.entries.lock().unwrap().iter().copied().collect()
+    }
+}
+
+/// Anything that can drive a single manual tick on the validator
+pub trait TickDriver {
+    /// Trigger one tick and wait until the validator has processed it
+    fn trigger_tick(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Ticks that make up one slot on the validator being driven
+    fn ticks_per_slot(&self) -> u64 {
+        DEFAULT_TICKS_PER_SLOT
+    }
+
+    /// Tick until `rpc` reports a slot past the current one, returning the new slot
+    ///
+    /// Gives up with `BridgeError::ChainStalled` after twice `ticks_per_slot`
+    /// ticks without the slot moving.
+    fn trigger_slot(
+        &self,
+        rpc: &dyn ConfirmationRpc,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let start_slot = rpc.get_slot()?;
+        let max_ticks = self.ticks_per_slot().max(1).saturating_mul(2);
+        for _ in 0..max_ticks {
+            self.trigger_tick()?;
+            let slot = rpc.get_slot()?;
+            if slot > start_slot {
+                return Ok(slot);
+            }
+        }
+        Err(Box::new(BridgeError::ChainStalled {
+            slot: start_slot,
+            attempts: u32::try_from(max_ticks).unwrap_or(u32::MAX),
+            last_tick_error: None,
+        }))
+    }
+}
+
+impl TickDriver for IpcClient {
+    fn trigger_tick(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.tick()? {
+            Ok(())
+        } else {
+            Err("Tick rejected by IPC server".into())
+        }
+    }
+}
+
+/// `TickDriver` for callers running inside a tokio runtime
+#[async_trait::async_trait]
+pub trait AsyncTickDriver: Send + Sync {
+    /// Trigger one tick and wait until the validator has processed it
+    async fn trigger_tick(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Runs a blocking `TickDriver` on tokio's blocking pool so ticking never
+/// stalls an executor thread
+pub struct BlockingTickDriver<T>(pub Arc<T>);
+
+#[async_trait::async_trait]
+impl<T: TickDriver + Send + Sync + 'static> AsyncTickDriver for BlockingTickDriver<T> {
+    async fn trigger_tick(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let tick_driver = self.0.clone();
+        tokio::task::spawn_blocking(move || tick_driver.trigger_tick()).await?
+    }
+}
+
+/// Ticks per slot assumed when the genesis config's value is not known
+pub const DEFAULT_TICKS_PER_SLOT: u64 = 2;
+
+/// Serializes tick+done exchanges on one pair of tick channels
+///
+/// The done channel carries no tick id, so every entry point ticking the same
+/// channels (`IpcServer`, `LocalTickClient`) must share one lock, or one could
+/// consume another's done signal.
+pub type TickLock = Arc<Mutex<()>>;
+
+/// One installation of the PoH service's tick channels
+#[derive(Debug, Clone)]
+pub struct TickChannels {
+    pub tick_sender: Sender<()>,
+    pub tick_done_receiver: Receiver<()>,
+    /// Starts at 1 and grows with every install; 0 for channels given directly
+    pub generation: u64,
+    /// `ticks_per_slot` of the bank the PoH service is ticking
+    pub ticks_per_slot: u64,
+}
+
+/// Owns the tick channels of the current PoH service
+///
+/// The PoH setup code installs fresh channels on every (re)start. Clients
+/// built with `LocalTickClient::from_registry` pick up the new channels the
+/// first time the old ones turn out to be disconnected.
+///
+/// Note: nothing installs channels through a registry yet. The PoH service is
+/// set up by `solana-test-validator`, which cannot depend on this crate, and
+/// `multivm_validator` hands its channels straight to `IpcServer` with no
+/// restart path; the registry is for embedders that restart the PoH service.
+#[derive(Debug, Default)]
+pub struct TickChannelRegistry {
+    current: RwLock<Option<TickChannels>>,
+}
+
+impl TickChannelRegistry {
+    /// Replace the current channels, returning their generation
+    ///
+    /// `ticks_per_slot` comes from the genesis config of the bank being ticked.
+    pub fn install(
+        &self,
+        tick_sender: Sender<()>,
+        tick_done_receiver: Receiver<()>,
+        ticks_per_slot: u64,
+    ) -> u64 {
+        let mut current = self.current.write().unwrap();
+        let generation = current.as_ref().map_or(0, |channels| channels.generation) + 1;
+        *current = Some(TickChannels {
+            tick_sender,
+            tick_done_receiver,
+            generation,
+            ticks_per_slot,
+        });
+        info!("Installed tick channels, generation {}", generation);
+        generation
+    }
+
+    pub fn current(&self) -> Option<TickChannels> {
+        self.current.read().unwrap().clone()
+    }
+}
+
+/// Drives ticks in-process over the validator's tick channels, without IPC
+///
+/// Each tick+done exchange holds the client's `TickLock`; share it with an
+/// `IpcServer` on the same channels through `with_tick_lock`.
+pub struct LocalTickClient {
+    channels: Mutex<TickChannels>,
+    tick_lock: TickLock,
+    registry: Option<Arc<TickChannelRegistry>>,
+    tick_counters: Arc<TickCounters>,
+    tick_ledger: Option<Arc<TickLedger>>,
+}
+
+impl LocalTickClient {
+    pub fn new(tick_sender: Sender<()>, tick_done_receiver: Receiver<()>) -> Self {
+        Self {
+            channels: Mutex::new(TickChannels {
+                tick_sender,
+                tick_done_receiver,
+                generation: 0,
+                ticks_per_slot: DEFAULT_TICKS_PER_SLOT,
+            }),
+            tick_lock: TickLock::default(),
+            registry: None,
+            tick_counters: Arc::default(),
+            tick_ledger: None,
+        }
+    }
+
+    /// Tick over the channels currently installed in `registry`
+    ///
+    /// A tick that finds its channels disconnected re-fetches them from the
+    /// registry once and is retried against the new generation.
+    pub fn from_registry(
+        registry: Arc<TickChannelRegistry>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let channels = registry.current().ok_or("No tick channels installed")?;
+        Ok(Self {
+            channels: Mutex::new(channels),
+            tick_lock: TickLock::default(),
+            registry: Some(registry),
+            tick_counters: Arc::default(),
+            tick_ledger: None,
+        })
+    }
+
+    /// Ticks per slot of a validator whose channels were given directly
+    ///
+    /// Clients built from a registry use the value installed with the channels.
+    pub fn with_ticks_per_slot(self, ticks_per_slot: u64) -> Self {
+        self.channels.lock().unwrap().ticks_per_slot = ticks_per_slot;
+        self
+    }
+
+    /// Record every tick driven by this client in `tick_ledger`
+    pub fn with_tick_ledger(mut self, tick_ledger: Arc<TickLedger>) -> Self {
+        self.tick_ledger = Some(tick_ledger);
+        self
+    }
+
+    /// Serialize ticks with the other entry points on the same channels
+    pub fn with_tick_lock(mut self, tick_lock: TickLock) -> Self {
+        self.tick_lock = tick_lock;
+        self
+    }
+
+    /// Generation of the channels the next tick goes to
+    pub fn generation(&self) -> u64 {
+        self.channels.lock().unwrap().generation
+    }
+
+    fn record_in_ledger(&self) {
+        if let Some(tick_ledger) = &self.tick_ledger {
+            tick_ledger.record();
+        }
+    }
+
+    /// Record ticks into counters shared with the other tick entry points
+    pub fn with_tick_counters(mut self, tick_counters: Arc<TickCounters>) -> Self {
+        self.tick_counters = tick_counters;
+        self
+    }
+
+    /// Tick over the current channels, moving to a new generation if needed
+    fn exchange_current(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Always taken before `channels`
+        let _tick_guard = self.tick_lock.lock().unwrap();
+        let mut channels = self.channels.lock().unwrap();
+        self.tick_counters.record(TickSource::Local);
+        let err = match exchange(&channels) {
+            Ok(()) => return Ok(()),
+            Err(err) => err,
+        };
+
+        // The PoH service may have restarted with new channels
+        let current = self
+            .registry
+            .as_ref()
+            .and_then(|registry| registry.current());
+        match current {
+            Some(current) if current.generation != channels.generation => {
+                info!(
+                    "Local tick client moving from generation {} to {}",
+                    channels.generation, current.generation
+                );
+                *channels = current;
+                exchange(&channels)
+            }
+            _ => Err(err),
+        }
+    }
+}
+
+impl TickDriver for LocalTickClient {
+    fn ticks_per_slot(&self) -> u64 {
+        self.channels.lock().unwrap().ticks_per_slot
+    }
+
+    fn trigger_tick(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.exchange_current()?;
+        // Outside the tick lock: the ledger's slot source may be slow
+        self.record_in_ledger();
+        Ok(())
+    }
+}
+
+fn exchange(channels: &TickChannels) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    channels
+        .tick_sender
+        .send(())
+        .map_err(|_| "Tick channel disconnected")?;
+    channels
+        .tick_done_receiver
+        .recv()
+        .map_err(|_| "Tick done channel disconnected")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::unbounded,
+        std::{
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+            thread,
+        },
+    };
+
+    #[test]
+    fn test_local_tick_client_concurrent_ticks() {
+        let (tick_sender, tick_receiver) = unbounded();
+        let (tick_done_sender, tick_done_receiver) = unbounded();
+        // Stand-in for the validator: acknowledge every tick it receives
+        let processed = Arc::new(AtomicUsize::new(0));
+        let validator = {
+            let processed = processed.clone();
+            thread::spawn(move || {
+                while tick_receiver.recv().is_ok() {
+                    processed.fetch_add(1, Ordering::SeqCst);
+                    if tick_done_sender.send(()).is_err() {
+                        break;
+                    }
+                }
+            })
+        };
+
+        let client = Arc::new(LocalTickClient::new(tick_sender, tick_done_receiver));
+        let ticks_per_thread = 100;
+        let returned = (0..2)
+            .map(|_| {
+                let client = client.clone();
+                thread::spawn(move || {
+                    (0..ticks_per_thread)
+                        .map(|_| client.trigger_tick().map(|_| 1).unwrap())
+                        .sum::<usize>()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(returned, vec![ticks_per_thread, ticks_per_thread]);
+        assert_eq!(processed.load(Ordering::SeqCst), 2 * ticks_per_thread);
+        // Every done signal was consumed by the tick that produced it
+        assert!(client
+            .channels
+            .lock()
+            .unwrap()
+            .tick_done_receiver
+            .is_empty());
+
+        drop(client);
+        validator.join().unwrap();
+    }
+
+    #[test]
+    fn test_local_tick_client_follows_registry_swap() {
+        let registry = Arc::new(TickChannelRegistry::default());
+        let new_processed = Arc::new(AtomicUsize::new(0));
+
+        // Stand-in PoH: acknowledge two ticks, then restart while holding the third
+        let (tick_sender, tick_receiver) = unbounded();
+        let (tick_done_sender, tick_done_receiver) = unbounded();
+        assert_eq!(registry.install(tick_sender, tick_done_receiver, 2), 1);
+        let old_service = {
+            let registry = registry.clone();
+            let new_processed = new_processed.clone();
+            thread::spawn(move || {
+                for _ in 0..2 {
+                    tick_receiver.recv().unwrap();
+                    tick_done_sender.send(()).unwrap();
+                }
+                tick_receiver.recv().unwrap();
+
+                let (tick_sender, tick_receiver) = unbounded();
+                let (tick_done_sender, tick_done_receiver) = unbounded();
+                assert_eq!(registry.install(tick_sender, tick_done_receiver, 2), 2);
+                thread::spawn(move || {
+                    while tick_receiver.recv().is_ok() {
+                        new_processed.fetch_add(1, Ordering::SeqCst);
+                        if tick_done_sender.send(()).is_err() {
+                            break;
+                        }
+                    }
+                });
+                // Returning drops the old channels without acknowledging
+            })
+        };
+
+        let client = LocalTickClient::from_registry(registry.clone()).unwrap();
+        assert_eq!(client.generation(), 1);
+        client.trigger_tick().unwrap();
+        client.trigger_tick().unwrap();
+        // In flight when the service restarts
+        client.trigger_tick().unwrap();
+        assert_eq!(client.generation(), 2);
+        old_service.join().unwrap();
+
+        for _ in 0..3 {
+            client.trigger_tick().unwrap();
+        }
+        assert_eq!(new_processed.load(Ordering::SeqCst), 4);
+
+        // Channels given directly have no registry to fall back on
+        let (tick_sender, _) = unbounded();
+        let (_, tick_done_receiver) = unbounded();
+        let detached = LocalTickClient::new(tick_sender, tick_done_receiver);
+        assert!(detached.trigger_tick().is_err());
+    }
+
+    /// Slot source that advances one slot every `ticks_per_slot` acknowledged ticks
+    struct TickedSlots {
+        ticks: Arc<AtomicU64>,
+        ticks_per_slot: u64,
+    }
+
+    impl ConfirmationRpc for TickedSlots {
+        fn get_signature_status_with_commitment(
+            &self,
+            _signature: &solana_sdk::signature::Signature,
+            _commitment_config: solana_sdk::commitment_config::CommitmentConfig,
+        ) -> solana_rpc_client_api::client_error::Result<
+            Option<solana_transaction_error::TransactionResult<()>>,
+        > {
+            Ok(None)
+        }
+
+        fn get_block_height(&self) -> solana_rpc_client_api::client_error::Result<u64> {
+            self.get_slot()
+        }
+
+        fn get_slot(&self) -> solana_rpc_client_api::client_error::Result<u64> {
+            Ok(self.ticks.load(Ordering::SeqCst) / self.ticks_per_slot)
+        }
+    }
+
+    #[test]
+    fn test_trigger_slot_uses_installed_ticks_per_slot() {
+        let registry = Arc::new(TickChannelRegistry::default());
+        let ticks = Arc::new(AtomicU64::new(0));
+        let (tick_sender, tick_receiver) = unbounded();
+        let (tick_done_sender, tick_done_receiver) = unbounded();
+        registry.install(tick_sender, tick_done_receiver, 4);
+        let validator = {
+            let ticks = ticks.clone();
+            thread::spawn(move || {
+                while tick_receiver.recv().is_ok() {
+                    ticks.fetch_add(1, Ordering::SeqCst);
+                    if tick_done_sender.send(()).is_err() {
+                        break;
+                    }
+                }
+            })
+        };
+
+        let client = LocalTickClient::from_registry(registry.clone()).unwrap();
+        assert_eq!(client.ticks_per_slot(), 4);
+        let slots = TickedSlots {
+            ticks: ticks.clone(),
+            ticks_per_slot: 4,
+        };
+        assert_eq!(client.trigger_slot(&slots).unwrap(), 1);
+        assert_eq!(ticks.load(Ordering::SeqCst), 4);
+        // Starting mid-slot only ticks to the next boundary
+        client.trigger_tick().unwrap();
+        assert_eq!(client.trigger_slot(&slots).unwrap(), 2);
+        assert_eq!(ticks.load(Ordering::SeqCst), 8);
+
+        // A slot that never moves is reported as a stall
+        let frozen = TickedSlots {
+            ticks: Arc::new(AtomicU64::new(0)),
+            ticks_per_slot: u64::MAX,
+        };
+        let err = client.trigger_slot(&frozen).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BridgeError>(),
+            Some(BridgeError::ChainStalled { attempts: 8, .. })
+        ));
+        assert_eq!(ticks.load(Ordering::SeqCst), 16);
+
+        drop(client);
+        drop(registry);
+        validator.join().unwrap();
+    }
+
+    #[test]
+    fn test_tick_ledger_keeps_last_ticks_in_sequence() {
+        let slot = Arc::new(AtomicU64::new(0));
+        let ledger = {
+            let slot = slot.clone();
+            TickLedger::new(3).with_slot_source(Box::new(move || Some(slot.load(Ordering::SeqCst))))
+        };
+        assert!(ledger.entries().is_empty());
+
+        // Two ticks per slot
+        for tick in 1..=5u64 {
+            slot.store(tick / 2, Ordering::SeqCst);
+            ledger.record();
+        }
+        let entries = ledger.entries();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.sequence, entry.resulting_slot))
+                .collect::<Vec<_>>(),
+            vec![(2, Some(1)), (3, Some(2)), (4, Some(2))]
+        );
+        assert!(entries
+            .windows(2)
+            .all(|pair| pair[0].timestamp_ms <= pair[1].timestamp_ms));
+
+        // Without a slot source the slot is unknown
+        let ledger = TickLedger::new(2);
+        ledger.record();
+        assert_eq!(ledger.entries()[0].resulting_slot, None);
+    }
+}
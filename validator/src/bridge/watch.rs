@@ -0,0 +1,374 @@
+use {
+    crossbeam_channel::{unbounded, Receiver, RecvTimeoutError},
+    log::{debug, info, warn},
+    solana_account_decoder::UiAccountEncoding,
+    solana_client::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    solana_rpc_client_api::config::RpcAccountInfoConfig,
+    solana_sdk::{
+        account::{Account, AccountSharedData},
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+    },
+    std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, JoinHandle},
+        time::Duration,
+    },
+};
+
+/// Callback invoked with the new state of a watched account
+pub type AccountCallback = Box<dyn Fn(&Pubkey, &AccountSharedData) + Send + Sync>;
+
+/// Delay before re-subscribing after a dropped subscription
+pub const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// How often the watcher threads check for shutdown while waiting for updates
+const EXIT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Source of pushed account updates
+///
+/// The returned receiver yields every new state of `pubkey` and disconnects
+/// when the underlying subscription is lost, which makes the watcher resubscribe.
+pub trait AccountSubscriber: Send + Sync {
+    fn subscribe(
+        &self,
+        pubkey: &Pubkey,
+    ) -> Result<Receiver<AccountSharedData>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Source of account snapshots for the polling fallback
+pub trait AccountFetcher: Send + Sync {
+    fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl AccountFetcher for RpcClient {
+    fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(RpcClient::get_multiple_accounts(self, pubkeys)?)
+    }
+}
+
+/// `accountSubscribe` over the validator websocket endpoint
+pub struct PubsubAccountSubscriber {
+    ws_url: String,
+}
+
+impl PubsubAccountSubscriber {
+    pub fn new(ws_url: String) -> Self {
+        Self { ws_url }
+    }
+}
+
+impl AccountSubscriber for PubsubAccountSubscriber {
+    fn subscribe(
+        &self,
+        pubkey: &Pubkey,
+    ) -> Result<Receiver<AccountSharedData>, Box<dyn std::error::Error + Send + Sync>> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::processed()),
+            ..RpcAccountInfoConfig::default()
+        };
+        let (subscription, updates) =
+            PubsubClient::account_subscribe(&self.ws_url, pubkey, Some(config))?;
+        let (sender, receiver) = unbounded();
+        let pubkey = *pubkey;
+        // Keep the subscription alive while forwarding decoded updates; when the
+        // websocket goes away `updates` disconnects and so does `receiver`.
+        thread::spawn(move || {
+            let _subscription = subscription;
+            for update in updates.iter() {
+                match update.value.decode::<AccountSharedData>() {
+                    Some(account) => {
+                        if sender.send(account).is_err() {
+                            break;
+                        }
+                    }
+                    None => warn!("Failed to decode account update for {}", pubkey),
+                }
+            }
+        });
+        Ok(receiver)
+    }
+}
+
+/// Watches a fixed set of accounts and drives callbacks when they change
+///
+/// Updates come from websocket subscriptions (`start`) or, on the manually ticked
+/// validator where notifications only arrive after ticks, from periodic
+/// `get_multiple_accounts` polling (`poll_fallback`).
+pub struct AccountWatcher {
+    ws_url: String,
+    accounts: Vec<Pubkey>,
+    latest: Arc<RwLock<HashMap<Pubkey, AccountSharedData>>>,
+    callbacks: Arc<RwLock<Vec<AccountCallback>>>,
+    exit: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl AccountWatcher {
+    /// Create a watcher for `accounts`; nothing is watched until `start` or `poll_fallback`
+    pub fn new(ws_url: String, accounts: Vec<Pubkey>) -> Self {
+        Self {
+            ws_url,
+            accounts,
+            latest: Arc::new(RwLock::new(HashMap::new())),
+            callbacks: Arc::new(RwLock::new(Vec::new())),
+            exit: Arc::new(AtomicBool::new(false)),
+            threads: Vec::new(),
+        }
+    }
+
+    /// Register a callback fired on every observed change of a watched account
+    pub fn on_change(&self, callback: AccountCallback) {
+        self.callbacks.write().unwrap().push(callback);
+    }
+
+    /// Last seen state of `pubkey`, if any update has been observed
+    pub fn latest(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.latest.read().unwrap().get(pubkey).cloned()
+    }
+
+    /// Subscribe to all watched accounts over the configured websocket endpoint
+    pub fn start(&mut self) {
+        let subscriber = Arc::new(PubsubAccountSubscriber::new(self.ws_url.clone()));
+        self.start_with_subscriber(subscriber, DEFAULT_RECONNECT_DELAY);
+    }
+
+    /// Subscribe to all watched accounts through `subscriber`, resubscribing after
+    /// `reconnect_delay` whenever a subscription fails or disconnects
+    pub fn start_with_subscriber(
+        &mut self,
+        subscriber: Arc<dyn AccountSubscriber>,
+        reconnect_delay: Duration,
+    ) {
+        for pubkey in self.accounts.clone() {
+            let subscriber = subscriber.clone();
+            let latest = self.latest.clone();
+            let callbacks = self.callbacks.clone();
+            let exit = self.exit.clone();
+            self.threads.push(thread::spawn(move || {
+                while !exit.load(Ordering::Relaxed) {
+                    match subscriber.subscribe(&pubkey) {
+                        Ok(updates) => {
+                            info!("Subscribed to account {}", pubkey);
+                            loop {
+                                if exit.load(Ordering::Relaxed) {
+                                    return;
+                                }
+                                match updates.recv_timeout(EXIT_CHECK_INTERVAL) {
+                                    Ok(account) => {
+                                        Self::apply_update(&latest, &callbacks, &pubkey, account)
+                                    }
+                                    Err(RecvTimeoutError::Timeout) => {}
+                                    Err(RecvTimeoutError::Disconnected) => {
+                                        warn!("Subscription to {} dropped, resubscribing", pubkey);
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Failed to subscribe to account {}: {}", pubkey, e);
+                        }
+                    }
+                    thread::sleep(reconnect_delay);
+                }
+            }));
+        }
+    }
+
+    /// Poll all watched accounts every `interval` instead of relying on notifications
+    pub fn poll_fallback(&mut self, fetcher: Arc<dyn AccountFetcher>, interval: Duration) {
+        let accounts = self.accounts.clone();
+        let latest = self.latest.clone();
+        let callbacks = self.callbacks.clone();
+        let exit = self.exit.clone();
+        self.threads.push(thread::spawn(move || {
+            while !exit.load(Ordering::Relaxed) {
+                match fetcher.get_multiple_accounts(&accounts) {
+                    Ok(fetched) => {
+                        for (pubkey, account) in accounts.iter().zip(fetched) {
+                            if let Some(account) = account {
+                                Self::apply_update(
+                                    &latest,
+                                    &callbacks,
+                                    pubkey,
+                                    AccountSharedData::from(account),
+                                );
+                            }
+                        }
+                    }
+                    Err(e) => warn!("Failed to poll watched accounts: {}", e),
+                }
+                thread::sleep(interval);
+            }
+        }));
+    }
+
+    /// Stop all watcher threads
+    pub fn stop(&mut self) {
+        self.exit.store(true, Ordering::Relaxed);
+        for handle in self.threads.drain(..) {
+            let _ = handle.join();
+        }
+    }
+
+    /// Record `account` as the latest state and fire callbacks if it changed
+    fn apply_update(
+        latest: &RwLock<HashMap<Pubkey, AccountSharedData>>,
+        callbacks: &RwLock<Vec<AccountCallback>>,
+        pubkey: &Pubkey,
+        account: AccountSharedData,
+    ) {
+        {
+            let mut latest = latest.write().unwrap();
+            if latest.get(pubkey) == Some(&account) {
+                return;
+            }
+            latest.insert(*pubkey, account.clone());
+        }
+        debug!("Watched account {} changed", pubkey);
+        for callback in callbacks.read().unwrap().iter() {
+            callback(pubkey, &account);
+        }
+    }
+}
+
+impl Drop for AccountWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::Sender,
+        solana_sdk::{account::ReadableAccount, system_program},
+        std::sync::{atomic::AtomicUsize, Mutex},
+    };
+
+    /// Hands out pre-registered update channels, one per subscribe call
+    struct MockSubscriber {
+        channels: Mutex<Vec<Receiver<AccountSharedData>>>,
+        subscribe_count: AtomicUsize,
+    }
+
+    impl AccountSubscriber for MockSubscriber {
+        fn subscribe(
+            &self,
+            _pubkey: &Pubkey,
+        ) -> Result<Receiver<AccountSharedData>, Box<dyn std::error::Error + Send + Sync>> {
+            self.subscribe_count.fetch_add(1, Ordering::SeqCst);
+            let mut channels = self.channels.lock().unwrap();
+            if channels.is_empty() {
+                return Err("no more subscriptions".into());
+            }
+            Ok(channels.remove(0))
+        }
+    }
+
+    /// Returns the accounts currently stored in `accounts`
+    struct MockFetcher {
+        accounts: Mutex<Vec<Option<Account>>>,
+    }
+
+    impl AccountFetcher for MockFetcher {
+        fn get_multiple_accounts(
+            &self,
+            _pubkeys: &[Pubkey],
+        ) -> Result<Vec<Option<Account>>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(self.accounts.lock().unwrap().clone())
+        }
+    }
+
+    fn account(lamports: u64) -> AccountSharedData {
+        AccountSharedData::new(lamports, 0, &system_program::id())
+    }
+
+    fn wait_for(condition: impl Fn() -> bool) {
+        for _ in 0..100 {
+            if condition() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("condition not reached");
+    }
+
+    #[test]
+    fn test_watcher_callbacks_and_resubscribe() {
+        let vault = Pubkey::new_unique();
+        let (first_sender, first_receiver) = unbounded();
+        let (second_sender, second_receiver): (Sender<AccountSharedData>, _) = unbounded();
+        let subscriber = Arc::new(MockSubscriber {
+            channels: Mutex::new(vec![first_receiver, second_receiver]),
+            subscribe_count: AtomicUsize::new(0),
+        });
+
+        let mut watcher = AccountWatcher::new("ws://unused".to_string(), vec![vault]);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        watcher.on_change(Box::new(move |pubkey, account| {
+            seen_clone
+                .lock()
+                .unwrap()
+                .push((*pubkey, account.lamports()));
+        }));
+        watcher.start_with_subscriber(subscriber.clone(), Duration::from_millis(10));
+
+        first_sender.send(account(100)).unwrap();
+        // Unchanged state does not fire the callback again
+        first_sender.send(account(100)).unwrap();
+        wait_for(|| watcher.latest(&vault).map(|a| a.lamports()) == Some(100));
+
+        // Drop the first subscription, the watcher must resubscribe and keep going
+        drop(first_sender);
+        wait_for(|| subscriber.subscribe_count.load(Ordering::SeqCst) >= 2);
+        second_sender.send(account(250)).unwrap();
+        wait_for(|| watcher.latest(&vault).map(|a| a.lamports()) == Some(250));
+
+        watcher.stop();
+        assert_eq!(*seen.lock().unwrap(), vec![(vault, 100), (vault, 250)]);
+    }
+
+    #[test]
+    fn test_watcher_poll_fallback() {
+        let vault = Pubkey::new_unique();
+        let fee_config = Pubkey::new_unique();
+        let fetcher = Arc::new(MockFetcher {
+            accounts: Mutex::new(vec![Some(Account::from(account(1))), None]),
+        });
+
+        let mut watcher = AccountWatcher::new("ws://unused".to_string(), vec![vault, fee_config]);
+        let changes = Arc::new(AtomicUsize::new(0));
+        let changes_clone = changes.clone();
+        watcher.on_change(Box::new(move |_, _| {
+            changes_clone.fetch_add(1, Ordering::SeqCst);
+        }));
+        watcher.poll_fallback(fetcher.clone(), Duration::from_millis(5));
+
+        wait_for(|| watcher.latest(&vault).is_some());
+        assert!(watcher.latest(&fee_config).is_none());
+
+        *fetcher.accounts.lock().unwrap() = vec![
+            Some(Account::from(account(1))),
+            Some(Account::from(account(7))),
+        ];
+        wait_for(|| watcher.latest(&fee_config).map(|a| a.lamports()) == Some(7));
+
+        watcher.stop();
+        assert_eq!(changes.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,258 @@
+//! The bridge deposit flow end to end against an in-memory chain: build a
+//! transfer with an EVM memo, send and confirm it through `LocalTickClient`,
+//! then scan the sealed block and parse the deposit back out.
+
+use {
+    agave_validator::bridge::{
+        index::DepositEvent,
+        tick::LocalTickClient,
+        util::{
+            create_transfer_with_evm_memo, parse_transfer_transaction_with_context,
+            send_and_confirm_transaction_with_driver, SendConfig,
+        },
+    },
+    base64::{prelude::BASE64_STANDARD, Engine},
+    crossbeam_channel::{unbounded, Receiver, Sender},
+    solana_client::{
+        rpc_client::{RpcClient, RpcClientConfig},
+        rpc_sender::{RpcSender, RpcTransportStats},
+    },
+    solana_rpc_client_api::{
+        client_error::{ErrorKind as ClientErrorKind, Result as ClientResult},
+        config::RpcBlockConfig,
+        request::RpcRequest,
+    },
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signature, Signer},
+        transaction::Transaction,
+    },
+    solana_transaction_status_client_types::{
+        EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
+        TransactionDetails, UiConfirmedBlock, UiTransactionEncoding,
+    },
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    },
+};
+
+const EVM_ADDRESS: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+/// Chain state shared by the RPC stand-in and the PoH stand-in
+#[derive(Default)]
+struct Chain {
+    slot: u64,
+    pending: Vec<Transaction>,
+    blocks: HashMap<u64, Vec<Transaction>>,
+    landed: HashMap<Signature, u64>,
+}
+
+impl Chain {
+    /// Every tick seals one slot holding whatever was sent since the last one
+    fn seal_slot(&mut self) {
+        self.slot += 1;
+        let transactions = std::mem::take(&mut self.pending);
+        for transaction in &transactions {
+            self.landed.insert(transaction.signatures[0], self.slot);
+        }
+        self.blocks.insert(self.slot, transactions);
+    }
+
+    fn block(&self, slot: u64) -> Option<UiConfirmedBlock> {
+        let transactions = self.blocks.get(&slot)?;
+        Some(UiConfirmedBlock {
+            previous_blockhash: Hash::default().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot: slot - 1,
+            transactions: Some(
+                transactions
+                    .iter()
+                    .map(|tx| EncodedTransactionWithStatusMeta {
+                        transaction: EncodedTransaction::Binary(
+                            BASE64_STANDARD.encode(bincode::serialize(tx).unwrap()),
+                            TransactionBinaryEncoding::Base64,
+                        ),
+                        meta: None,
+                        version: None,
+                    })
+                    .collect(),
+            ),
+            signatures: None,
+            rewards: None,
+            num_reward_partitions: None,
+            block_time: None,
+            block_height: Some(slot),
+        })
+    }
+}
+
+struct ChainSender(Arc<Mutex<Chain>>);
+
+#[async_trait::async_trait]
+impl RpcSender for ChainSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let mut chain = self.0.lock().unwrap();
+        match request {
+            RpcRequest::SendTransaction => {
+                let wire = BASE64_STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+                let transaction: Transaction = bincode::deserialize(&wire).unwrap();
+                let signature = transaction.signatures[0];
+                chain.pending.push(transaction);
+                Ok(serde_json::json!(signature.to_string()))
+            }
+            RpcRequest::GetSignatureStatuses => {
+                let statuses = params[0]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|signature| {
+                        let signature: Signature = signature.as_str().unwrap().parse().unwrap();
+                        chain.landed.get(&signature).map(|slot| {
+                            serde_json::json!({
+                                "slot": slot,
+                                "confirmations": 0,
+                                "status": {"Ok": null},
+                                "err": null,
+                                "confirmationStatus": "processed",
+                            })
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                Ok(serde_json::json!({"context": {"slot": chain.slot}, "value": statuses}))
+            }
+            RpcRequest::GetBlock => {
+                let slot = params[0].as_u64().unwrap();
+                let block = chain
+                    .block(slot)
+                    .ok_or_else(|| ClientErrorKind::Custom(format!("no block at slot {slot}")))?;
+                Ok(serde_json::to_value(block).unwrap())
+            }
+            _ => Err(ClientErrorKind::Custom(format!("unexpected request {request}")).into()),
+        }
+    }
+
+    async fn send_with_auth_token(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+        _auth_token: String,
+    ) -> ClientResult<serde_json::Value> {
+        self.send(request, params).await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "in-memory-chain".to_string()
+    }
+}
+
+/// Stand-in for the PoH service: seal a slot per tick, then report it done
+fn spawn_poh(
+    chain: Arc<Mutex<Chain>>,
+    tick_receiver: Receiver<()>,
+    tick_done_sender: Sender<()>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        while tick_receiver.recv().is_ok() {
+            chain.lock().unwrap().seal_slot();
+            if tick_done_sender.send(()).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[test]
+fn test_deposit_flow_in_process() {
+    let chain = Arc::new(Mutex::new(Chain::default()));
+    let (tick_sender, tick_receiver) = unbounded();
+    let (tick_done_sender, tick_done_receiver) = unbounded();
+    let poh = spawn_poh(chain.clone(), tick_receiver, tick_done_sender);
+    let tick_client = LocalTickClient::new(tick_sender, tick_done_receiver);
+
+    let mut rpc_client = RpcClient::new_sender(
+        ChainSender(chain.clone()),
+        RpcClientConfig::with_commitment(CommitmentConfig::processed()),
+    );
+    rpc_client.set_auth_token_secret(
+        "bd1fa71e224227a12439367e525610e7c0d242ecfa595ec471299b535e5d179d".to_string(),
+    );
+
+    // Create
+    let payer = Keypair::new();
+    let vault = Pubkey::new_unique();
+    let transaction =
+        create_transfer_with_evm_memo(&payer, &vault, 1_000_000, EVM_ADDRESS, Hash::new_unique())
+            .unwrap();
+
+    // Send and confirm
+    let config = SendConfig {
+        poll_interval: Duration::ZERO,
+        ..SendConfig::default()
+    };
+    let signature =
+        send_and_confirm_transaction_with_driver(&tick_client, &rpc_client, &transaction, &config)
+            .unwrap();
+    assert_eq!(signature, transaction.signatures[0]);
+
+    // Scan
+    let slot = rpc_client
+        .get_signature_statuses(&[signature])
+        .unwrap()
+        .value[0]
+        .as_ref()
+        .expect("confirmed deposit has a status")
+        .slot;
+    let block = rpc_client
+        .get_block_with_config(
+            slot,
+            RpcBlockConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                transaction_details: Some(TransactionDetails::Full),
+                rewards: Some(false),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .unwrap();
+    let scanned = block
+        .transactions
+        .unwrap()
+        .into_iter()
+        .filter_map(|tx| tx.transaction.decode()?.into_legacy_transaction())
+        .find(|tx| tx.signatures[0] == signature)
+        .expect("deposit is in the block it landed in");
+
+    // Parse
+    let deposit = parse_transfer_transaction_with_context(&scanned, slot)
+        .unwrap()
+        .expect("deposit parses as a transfer with memo");
+    assert_eq!(
+        deposit,
+        DepositEvent {
+            signature,
+            slot,
+            from: payer.pubkey(),
+            to: vault,
+            lamports: 1_000_000,
+            evm_address: EVM_ADDRESS.to_string(),
+            nonce: None,
+            duplicate_of: None,
+        }
+    );
+
+    drop(tick_client);
+    poh.join().unwrap();
+}
@@ -0,0 +1,25 @@
+use {
+    agave_validator::bridge::{
+        ipc::IpcClient,
+        selftest::{run_selftest, SelftestOptions},
+    },
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::commitment_config::CommitmentConfig,
+};
+
+#[test]
+#[ignore] // Requires manual execution with validator running
+fn test_bridge_selftest() {
+    let mut rpc_client = RpcClient::new_with_commitment(
+        "http://127.0.0.1:8899".to_string(),
+        CommitmentConfig::processed(),
+    );
+    rpc_client.set_auth_token_secret(
+        "bd1fa71e224227a12439367e525610e7c0d242ecfa595ec471299b535e5d179d".to_string(),
+    );
+    let ipc_client = IpcClient::new("/tmp/solana-private-validator".to_string());
+
+    let report = run_selftest(&rpc_client, &ipc_client, &SelftestOptions::default());
+    print!("{report}");
+    assert!(report.passed(), "{}", report);
+}
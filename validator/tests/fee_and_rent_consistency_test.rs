@@ -11,14 +11,10 @@ use {
         native_token::LAMPORTS_PER_SOL,
         rent::Rent,
     },
-    std::{
-        thread,
-        time::Duration,
-    },
 };
 
 use agave_validator::bridge::ipc::IpcClient;
-use agave_validator::bridge::util::send_and_confirm_transaction;
+use agave_validator::bridge::util::{send_and_confirm_transaction, tick_until_confirmed};
 
 #[test]
 #[ignore]
@@ -45,39 +41,10 @@ fn test_fee_consistency_across_block_heights() {
         .request_airdrop(&from.pubkey(), airdrop_amount)
         .expect("Failed to request airdrop");
 
-    // 等待空投确认
-    for _ in 0..10 {
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_millis(500));
-    }
-
-    let mut airdrop_confirmed = false;
-    for attempt in 1..=60 {
-        match rpc_client.get_signature_status(&airdrop_signature) {
-            Ok(Some(Ok(_))) => {
-                airdrop_confirmed = true;
-                println!("✅ 空投确认成功");
-                break;
-            }
-            Ok(Some(Err(e))) => {
-                panic!("空投失败: {}", e);
-            }
-            Ok(None) => {
-                thread::sleep(Duration::from_millis(500));
-            }
-            Err(e) => {
-                println!("检查空投状态时出错 (尝试 {}): {}", attempt, e);
-                thread::sleep(Duration::from_millis(500));
-            }
-        }
-    }
-
-    if !airdrop_confirmed {
-        let balance = rpc_client.get_balance(&from.pubkey()).unwrap_or(0);
-        if balance < airdrop_amount {
-            panic!("空投确认超时，当前余额: {} lamports", balance);
-        }
-    }
+    // 驱动tick直到空投确认
+    tick_until_confirmed(&ipc_client, &rpc_client, &airdrop_signature, 20)
+        .expect("空投未确认");
+    println!("✅ 空投确认成功");
 
     let balance = rpc_client.get_balance(&from.pubkey()).unwrap();
     println!("账户余额: {} lamports", balance);
@@ -108,14 +75,16 @@ fn test_fee_consistency_across_block_heights() {
         transaction.sign(&[&from], current_blockhash);
         
         println!("发送交易...");
-        let send_result = send_and_confirm_transaction(&ipc_client, &rpc_client, &transaction);
+        let send_result = send_and_confirm_transaction(
+            &ipc_client,
+            &rpc_client,
+            &transaction,
+            "bd1fa71e224227a12439367e525610e7c0d242ecfa595ec471299b535e5d179d",
+        );
         match send_result{
             Ok(signature) => {
                 println!("交易已发送，签名: {}", signature);
                 
-                // 等待交易确认
-                thread::sleep(Duration::from_secs(2));
-                
                 match rpc_client.get_signature_status_with_commitment(
                     &signature,
                     CommitmentConfig {
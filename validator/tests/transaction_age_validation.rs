@@ -1,5 +1,4 @@
 use log::{debug, error, info, warn};
-use solana_sdk::commitment_config::CommitmentLevel;
 use solana_sdk::signature::Signature;
 use {
     assert_cmd::prelude::*,
@@ -19,14 +18,11 @@ use {
         transaction::Transaction,
         native_token::LAMPORTS_PER_SOL,
     },
-    std::{
-        process::{Child, Command},
-        thread,
-        time::Duration,
-    },
+    std::process::{Child, Command},
 };
 
 use agave_validator::bridge::ipc::IpcClient;
+use agave_validator::bridge::util::{tick_until_confirmed, SlotClock};
 #[test]
 #[ignore] // Requires manual execution with validator running
 fn test_transaction_age_validation() {
@@ -45,79 +41,32 @@ fn test_transaction_age_validation() {
     // Airdrop SOL to sender account
     println!("Requesting airdrop for account: {}", from.pubkey());
     ipc_client.tick().unwrap();
-    thread::sleep(Duration::from_secs(1));
     let airdrop_amount = 2 * LAMPORTS_PER_SOL; // Airdrop 2 SOL
     let airdrop_signature = rpc_client
         .request_airdrop(&from.pubkey(), airdrop_amount)
         .expect("Failed to request airdrop");
 
-    // Wait for airdrop confirmation with longer timeout and better error handling
+    // Tick until the airdrop lands instead of sleeping
     println!("Waiting for airdrop confirmation...");
-    ipc_client.tick().unwrap();
-    thread::sleep(Duration::from_secs(1));
-    ipc_client.tick().unwrap();
-    thread::sleep(Duration::from_secs(1));
-    ipc_client.tick().unwrap();
-    thread::sleep(Duration::from_secs(1));
-    ipc_client.tick().unwrap();
-    thread::sleep(Duration::from_secs(1));
-    ipc_client.tick().unwrap();
-    thread::sleep(Duration::from_secs(1));
-
-    let mut airdrop_confirmed = false;
-    let max_attempts = 120; // Increased from 30 to 120 (60 seconds total)
-
-    for attempt in 1..=max_attempts {
-        if attempt % 10 == 0 {
-            println!("Airdrop confirmation attempt {}/{}", attempt, max_attempts);
-        }
-
-        match rpc_client.get_signature_status(&airdrop_signature) {
-            Ok(Some(Ok(_))) => {
-                airdrop_confirmed = true;
-                println!("Airdrop confirmed successfully");
-                break;
-            }
-            Ok(Some(Err(e))) => {
-                panic!("Airdrop failed with error: {}", e);
-            }
-            Ok(None) => {
-                // Transaction not yet processed, continue waiting
-                thread::sleep(Duration::from_millis(500));
-            }
-            Err(e) => {
-                println!("Warning: Error checking airdrop status (attempt {}): {}", attempt, e);
-                thread::sleep(Duration::from_millis(500));
-            }
-        }
-    }
-
-    if !airdrop_confirmed {
-        // Try to check balance anyway in case the signature status check failed
-        let balance = rpc_client.get_balance(&from.pubkey()).unwrap_or(0);
-        if balance >= airdrop_amount {
-            println!("Airdrop appears successful based on balance check: {} lamports", balance);
-        } else {
-            panic!("Airdrop confirmation timeout after {} attempts. Current balance: {} lamports",
-                   max_attempts, balance);
-        }
-    }
+    tick_until_confirmed(&ipc_client, &rpc_client, &airdrop_signature, 20)
+        .expect("Airdrop was not confirmed");
+    println!("Airdrop confirmed successfully");
     
     let balance = rpc_client.get_balance(&from.pubkey()).unwrap();
     println!("Airdrop successful, account balance: {} lamports", balance);
     
-    // Use ipc_client.tick() to advance nb_block_number slots
+    // Use ipc_client.tick_n() to advance nb_block_number slots
     println!("Starting to advance {} blocks...", nb_block_number);
     let initial_height = rpc_client.get_block_height().unwrap();
     
-    for _ in 0..nb_block_number {
-        // 2 ticks per block - each tick() call is synchronous and blocks until complete
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
-
-    }
+    // Ask the validator where it is instead of assuming slot 0 started at
+    // tick 0 - tick_n() drives them all over one connection and returns once
+    // the last one is done
+    let slot_clock = SlotClock::from_validator(&ipc_client, &rpc_client).unwrap();
+    let current_tick = ipc_client.get_stats().unwrap().total();
+    let target_slot = slot_clock.slot_at_height(initial_height + nb_block_number as u64);
+    let ticks = slot_clock.ticks_until_slot(current_tick, target_slot) as u32;
+    assert!(ipc_client.tick_n(ticks).unwrap());
 
     let final_height = rpc_client.get_block_height().unwrap();
     let blocks_advanced = final_height - initial_height;
@@ -147,36 +96,17 @@ fn test_transaction_age_validation() {
         expire_transaction.sign(&[&from], expire_block_hash);
         println!("Attempting to send transaction with expired blockhash...");
         ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
         // Send transaction, expecting it to be rejected
         let send_result = rpc_client.send_transaction(&expire_transaction);
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
 
         match send_result {
             Ok(signature) => {
-                thread::sleep(Duration::from_secs(2));
-                match rpc_client.get_signature_status_with_commitment(
-                    &signature,
-                    CommitmentConfig {
-                        commitment: CommitmentLevel::Processed,
-                    },
-                ) {
-                    Ok(Some(Ok(_))) => {
+                match tick_until_confirmed(&ipc_client, &rpc_client, &signature, 10) {
+                    Ok(()) => {
                         println!("✅ TEST PASSED: Transaction with expired blockhash should be confirmed!");
                     }
-                    Ok(Some(Err(e))) => {
-                        panic!("Transaction was eventually rejected, error: {}", e);
-                    }
-                    Ok(None) => {
-                        panic!("Transaction was not processed");
-                    }
                     Err(e) => {
-                        panic!("Error checking transaction status: {}", e);
+                        panic!("Transaction was not confirmed: {}", e);
                     }
                 }
             }
@@ -194,36 +124,17 @@ fn test_transaction_age_validation() {
         invalid_transaction.sign(&[&from], invalid_block_hash);
         println!("Attempting to send transaction with invalid blockhash...");
         ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
         // Send transaction, expecting it to be rejected
         let send_result = rpc_client.send_transaction(&invalid_transaction);
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
 
         match send_result {
             Ok(signature) => {
-                thread::sleep(Duration::from_secs(2));
-                match rpc_client.get_signature_status_with_commitment(
-                    &signature,
-                    CommitmentConfig {
-                        commitment: CommitmentLevel::Processed,
-                    },
-                ) {
-                    Ok(Some(Ok(_))) => {
+                match tick_until_confirmed(&ipc_client, &rpc_client, &signature, 10) {
+                    Ok(()) => {
                         panic!("TEST FAILED: Transaction with invalid blockhash should not be confirmed!");
                     }
-                    Ok(Some(Err(e))) => {
-                        println!("Transaction was eventually rejected, error: {}", e);
-                    }
-                    Ok(None) => {
-                        println!("Transaction was not processed");
-                    }
                     Err(e) => {
-                        println!("Error checking transaction status: {}", e);
+                        println!("Transaction was not confirmed: {}", e);
                     }
                 }
             }
@@ -242,36 +153,17 @@ fn test_transaction_age_validation() {
         invalid_transaction.sign(&[&from], valid_block_hash);
         println!("Attempting to send transaction with valid blockhash...");
         ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
         // Send transaction, expecting it to be rejected
         let send_result = rpc_client.send_transaction(&invalid_transaction);
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
-        ipc_client.tick().unwrap();
-        thread::sleep(Duration::from_secs(1));
 
         match send_result {
             Ok(signature) => {
-                thread::sleep(Duration::from_secs(2));
-                match rpc_client.get_signature_status_with_commitment(
-                    &signature,
-                    CommitmentConfig {
-                        commitment: CommitmentLevel::Processed,
-                    },
-                ) {
-                    Ok(Some(Ok(_))) => {
+                match tick_until_confirmed(&ipc_client, &rpc_client, &signature, 10) {
+                    Ok(()) => {
                         println!("✅ TEST PASSED: Transaction with valid blockhash is confirmed!");
                     }
-                    Ok(Some(Err(e))) => {
-                        panic!("TEST FAILED: Valid Transaction was eventually rejected, error: {}", e);
-                    }
-                    Ok(None) => {
-                        panic!("TEST FAILED: Valid Transaction was not processed");
-                    }
                     Err(e) => {
-                        panic!("TEST FAILED: Error checking valid transaction status: {}", e);
+                        panic!("TEST FAILED: Valid Transaction was not confirmed: {}", e);
                     }
                 }
             }